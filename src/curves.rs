@@ -0,0 +1,341 @@
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::traits::{Identity, VartimeMultiscalarMul};
+use curve25519_dalek::{constants, Scalar};
+use k256::elliptic_curve::hash2curve::{ExpandMsgXmd, GroupDigest};
+use k256::elliptic_curve::ops::MulByGenerator;
+use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use k256::elliptic_curve::PrimeField as _;
+use k256::{AffinePoint, EncodedPoint, FieldBytes, ProjectivePoint};
+use num_bigint::BigInt;
+use sha2::{Digest, Sha256, Sha512};
+use zeroize::Zeroize;
+
+use crate::vss::Group;
+
+// Elliptic-curve backends for Feldman commitments. Commitments are
+// compressed points (32 bytes for ristretto255, 33 for secp256k1) and
+// shares are scalars, so the sharing field is `PrimeField::new(order)`.
+
+// Prime order l = 2^252 + 27742317777372353535851937790883648493
+const RISTRETTO_ORDER: &str = "1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed";
+
+// Prime order n of the secp256k1 base point
+const SECP256K1_ORDER: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+
+// RFC 9380 domain separation tag for secp256k1 hash-to-curve
+const SECP256K1_DST: &[u8] = b"shamir-vss-V01-CS01-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+
+// Scalars are 32 bytes on both curves
+const SCALAR_LENGTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ristretto255;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Secp256k1;
+
+// `scalar` mod `order` as exactly 32 big-endian bytes
+fn scalar_bytes(scalar: &BigInt, order: &BigInt) -> [u8; SCALAR_LENGTH] {
+    let reduced = ((scalar % order) + order) % order;
+    let (_, mut bytes) = reduced.to_bytes_be();
+    let mut fixed = [0u8; SCALAR_LENGTH];
+    fixed[SCALAR_LENGTH - bytes.len()..].copy_from_slice(&bytes);
+    bytes.zeroize();
+    fixed
+}
+
+fn parse_order(hex: &str) -> BigInt {
+    BigInt::parse_bytes(hex.as_bytes(), 16).expect("Curve orders are valid hex")
+}
+
+impl Ristretto255 {
+    fn scalar(&self, value: &BigInt) -> Scalar {
+        let mut bytes = scalar_bytes(value, &self.order());
+        // dalek scalars are little-endian
+        bytes.reverse();
+        let scalar = Scalar::from_bytes_mod_order(bytes);
+        bytes.zeroize();
+        scalar
+    }
+}
+
+impl Group for Ristretto255 {
+    type Point = RistrettoPoint;
+
+    fn id(&self) -> String {
+        "ristretto255".to_string()
+    }
+
+    fn order(&self) -> BigInt {
+        parse_order(RISTRETTO_ORDER)
+    }
+
+    fn generator(&self) -> RistrettoPoint {
+        constants::RISTRETTO_BASEPOINT_POINT
+    }
+
+    fn identity(&self) -> RistrettoPoint {
+        RistrettoPoint::identity()
+    }
+
+    fn add(&self, a: &RistrettoPoint, b: &RistrettoPoint) -> RistrettoPoint {
+        a + b
+    }
+
+    fn mul(&self, point: &RistrettoPoint, scalar: &BigInt) -> RistrettoPoint {
+        let mut scalar = self.scalar(scalar);
+        let product = point * scalar;
+        scalar.zeroize();
+        product
+    }
+
+    fn point_to_bytes(&self, point: &RistrettoPoint) -> Vec<u8> {
+        point.compress().to_bytes().to_vec()
+    }
+
+    // Decompression rejects anything outside the prime-order group
+    fn point_from_bytes(&self, bytes: &[u8]) -> Option<RistrettoPoint> {
+        CompressedRistretto::from_slice(bytes).ok()?.decompress()
+    }
+
+    // dalek's Straus/Pippenger over its own point representation
+    fn multi_exp(&self, points: &[RistrettoPoint], scalars: &[BigInt]) -> RistrettoPoint {
+        let scalars: Vec<Scalar> = scalars.iter().map(|scalar| self.scalar(scalar)).collect();
+        let count = points.len().min(scalars.len());
+        RistrettoPoint::vartime_multiscalar_mul(&scalars[..count], &points[..count])
+    }
+
+    // Elligator map of a 64-byte SHA-512 digest
+    fn hash_to_point(&self, message: &[u8]) -> RistrettoPoint {
+        let digest = Sha512::new()
+            .chain_update(b"shamir-ristretto255-hash-to-group")
+            .chain_update(message)
+            .finalize();
+        RistrettoPoint::from_uniform_bytes(&digest.into())
+    }
+
+    // Uses the precomputed basepoint table
+    fn mul_generator(&self, scalar: &BigInt) -> RistrettoPoint {
+        let mut scalar = self.scalar(scalar);
+        let product = RistrettoPoint::mul_base(&scalar);
+        scalar.zeroize();
+        product
+    }
+}
+
+impl Secp256k1 {
+    fn scalar(&self, value: &BigInt) -> k256::Scalar {
+        let mut bytes = scalar_bytes(value, &self.order());
+        let scalar = k256::Scalar::from_repr(FieldBytes::from(bytes))
+            .expect("Reduced scalars are canonical");
+        bytes.zeroize();
+        scalar
+    }
+}
+
+impl Group for Secp256k1 {
+    type Point = ProjectivePoint;
+
+    fn id(&self) -> String {
+        "secp256k1".to_string()
+    }
+
+    fn order(&self) -> BigInt {
+        parse_order(SECP256K1_ORDER)
+    }
+
+    fn generator(&self) -> ProjectivePoint {
+        ProjectivePoint::GENERATOR
+    }
+
+    fn identity(&self) -> ProjectivePoint {
+        ProjectivePoint::IDENTITY
+    }
+
+    fn add(&self, a: &ProjectivePoint, b: &ProjectivePoint) -> ProjectivePoint {
+        a + b
+    }
+
+    fn mul(&self, point: &ProjectivePoint, scalar: &BigInt) -> ProjectivePoint {
+        let mut scalar = self.scalar(scalar);
+        let product = point * &scalar;
+        scalar.zeroize();
+        product
+    }
+
+    // SEC1 compressed, the identity encodes as a single zero byte
+    fn point_to_bytes(&self, point: &ProjectivePoint) -> Vec<u8> {
+        point.to_affine().to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    // Cofactor 1, so every point on the curve is in the group
+    fn point_from_bytes(&self, bytes: &[u8]) -> Option<ProjectivePoint> {
+        let encoded = EncodedPoint::from_bytes(bytes).ok()?;
+        let point: Option<AffinePoint> = AffinePoint::from_encoded_point(&encoded).into();
+        point.map(ProjectivePoint::from)
+    }
+
+    fn hash_to_point(&self, message: &[u8]) -> ProjectivePoint {
+        k256::Secp256k1::hash_from_bytes::<ExpandMsgXmd<Sha256>>(&[message], &[SECP256K1_DST])
+            .expect("The domain separation tag is short enough for expand_message_xmd")
+    }
+
+    fn mul_generator(&self, scalar: &BigInt) -> ProjectivePoint {
+        let mut scalar = self.scalar(scalar);
+        let product = ProjectivePoint::mul_by_generator(&scalar);
+        scalar.zeroize();
+        product
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shamir::SharmirModel;
+    use crate::share::Share;
+    use crate::vss::VSSCommitments;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn ristretto255_encoding() {
+        let group = Ristretto255;
+        let point = group.mul_generator(&BigInt::from(12345));
+        let bytes = group.point_to_bytes(&point);
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(group.point_from_bytes(&bytes), Some(point));
+
+        let identity = group.point_to_bytes(&group.identity());
+        assert_eq!(identity, vec![0u8; 32]);
+        assert_eq!(group.point_from_bytes(&identity), Some(group.identity()));
+
+        // Not a canonical encoding, and the wrong length
+        assert_eq!(group.point_from_bytes(&[0xff; 32]), None);
+        assert_eq!(group.point_from_bytes(&bytes[1..]), None);
+    }
+
+    #[test]
+    fn secp256k1_encoding() {
+        let group = Secp256k1;
+        let point = group.mul_generator(&BigInt::from(12345));
+        let bytes = group.point_to_bytes(&point);
+        assert_eq!(bytes.len(), 33);
+        assert!(bytes[0] == 2 || bytes[0] == 3);
+        assert_eq!(group.point_from_bytes(&bytes), Some(point));
+
+        let identity = group.point_to_bytes(&group.identity());
+        assert_eq!(identity, vec![0u8]);
+        assert_eq!(group.point_from_bytes(&identity), Some(group.identity()));
+
+        // x = 5 has no point on the curve
+        let mut off_curve = vec![2u8];
+        off_curve.extend_from_slice(&[0; 31]);
+        off_curve.push(5);
+        assert_eq!(group.point_from_bytes(&off_curve), None);
+    }
+
+    #[test]
+    fn scalars_reduce_mod_the_group_order() {
+        let secp = Secp256k1;
+        let n = secp.order();
+        assert_eq!(secp.scalar(&n), k256::Scalar::ZERO);
+        assert_eq!(secp.scalar(&(&n + 1u32)), k256::Scalar::ONE);
+        assert_eq!(secp.scalar(&BigInt::from(-1)), -k256::Scalar::ONE);
+
+        let ristretto = Ristretto255;
+        let l = ristretto.order();
+        assert_eq!(ristretto.scalar(&(&l + 2u32)), Scalar::from(2u8));
+        assert_eq!(
+            ristretto.mul_generator(&(&l + 7u32)),
+            ristretto.mul(&ristretto.generator(), &BigInt::from(7))
+        );
+    }
+
+    #[test]
+    fn hash_to_point_is_deterministic_and_separated() {
+        let ristretto = Ristretto255;
+        let a = ristretto.hash_to_point(b"a");
+        assert_eq!(ristretto.hash_to_point(b"a"), a);
+        assert_ne!(ristretto.hash_to_point(b"b"), a);
+        assert_ne!(a, ristretto.identity());
+
+        let secp = Secp256k1;
+        let a = secp.hash_to_point(b"a");
+        assert_eq!(secp.hash_to_point(b"a"), a);
+        assert_ne!(secp.hash_to_point(b"b"), a);
+        assert_ne!(a, secp.generator());
+    }
+
+    #[test]
+    fn feldman_over_ristretto255() {
+        let mut model = SharmirModel::with_vss(BigInt::from(99), 4, 2, Ristretto255).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+
+        for share in &shares {
+            assert_eq!(model.verify_share(share), Ok(true));
+            let wrong = share.expose_secret() + 1u32;
+            let forged = Share::new(
+                share.index.clone(),
+                wrong,
+                share.set_id,
+                share.threshold,
+                share.field_id.clone(),
+            );
+            assert_eq!(model.verify_share(&forged), Ok(false));
+        }
+    }
+
+    #[test]
+    fn feldman_over_secp256k1_from_encoded_commitments() {
+        let group = Secp256k1;
+        let secret = BigInt::from(99);
+        let coefficients = [secret, BigInt::from(5), BigInt::from(8)];
+        let commitments = VSSCommitments::new(&coefficients, &group);
+
+        // What a participant gets over the wire
+        let points = commitments
+            .points()
+            .iter()
+            .map(|point| {
+                group
+                    .point_from_bytes(&group.point_to_bytes(point))
+                    .unwrap()
+            })
+            .collect();
+        let received = VSSCommitments::from_points(points);
+
+        // f(3) = 99 + 15 + 72
+        let x = BigInt::from(3);
+        assert_eq!(
+            received.verify_share(&x, &BigInt::from(186), &group),
+            Ok(true)
+        );
+        assert_eq!(
+            received.verify_share(&x, &BigInt::from(187), &group),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn participant_keys_are_public_shares() {
+        let group = Secp256k1;
+        let mut model = SharmirModel::with_vss(BigInt::from(777), 4, 3, group).unwrap();
+        let indices: Vec<BigInt> = [3, 10, 250, 1000].map(BigInt::from).to_vec();
+        model.generate_shares_at(&indices, &mut OsRng).unwrap();
+        let shares = model.get_shares().clone();
+        let commitments = model.vss_commitments().unwrap();
+
+        assert_eq!(
+            commitments.public_key(),
+            Some(&group.mul_generator(&BigInt::from(777)))
+        );
+        let keys = commitments.participant_keys(&indices, &group);
+        assert_eq!(keys.len(), shares.len());
+        for (share, key) in shares.iter().zip(&keys) {
+            assert_eq!(key, &group.mul_generator(share.expose_secret()));
+        }
+        // Indices are reduced mod n like the shares
+        let wrapped = &indices[1] + group.order();
+        assert_eq!(commitments.participant_key(&wrapped, &group), keys[1]);
+    }
+}
@@ -0,0 +1,102 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    // Fewer shares than the threshold were given
+    InsufficientShares { needed: usize, got: usize },
+    // Two shares have the same x-coordinate
+    DuplicateIndex,
+    // A share sits at x = 0, which is the secret itself
+    ZeroIndex,
+    // Threshold is zero or larger than the number of shares
+    InvalidThreshold { threshold: usize, shares: usize },
+    // The field has fewer non-zero elements than shares requested
+    TooManyShares { shares: usize, max: usize },
+    // Number of explicit x-coordinates doesn't match the number of shares
+    IndexCountMismatch { expected: usize, got: usize },
+    // Shares come from different sharings, thresholds or fields
+    MismatchedShares,
+    // A secret or share value is not an element of the field
+    OutOfRange,
+    // No VSS commitments to verify against
+    MissingCommitments,
+    // Shares don't lie on a single polynomial of the threshold's degree
+    InconsistentShares,
+    // Too many shares are wrong for error correction to recover the secret
+    TooManyFaults { tolerated: usize },
+    // Finding the largest consistent subset would take too many subsets
+    TooManySubsets { max: usize },
+    // Shares at these positions of the input fail VSS verification
+    InvalidShares(Vec<usize>),
+    // The reconstructed secret doesn't open the VSS commitments
+    CommitmentMismatch,
+    // Pedersen verification needs the share's blinding value
+    MissingBlinding,
+    // Field or VSS group parameters are unusable
+    InvalidParams(&'static str),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InsufficientShares { needed, got } => {
+                write!(f, "need at least {} shares, got {}", needed, got)
+            }
+            Error::DuplicateIndex => write!(f, "two shares have the same index"),
+            Error::ZeroIndex => write!(f, "share index must not be zero"),
+            Error::InvalidThreshold { threshold, shares } => write!(
+                f,
+                "threshold {} must be between 1 and the number of shares ({})",
+                threshold, shares
+            ),
+            Error::TooManyShares { shares, max } => {
+                write!(
+                    f,
+                    "field supports at most {} shares, asked for {}",
+                    max, shares
+                )
+            }
+            Error::IndexCountMismatch { expected, got } => {
+                write!(f, "expected {} share indices, got {}", expected, got)
+            }
+            Error::MismatchedShares => {
+                write!(
+                    f,
+                    "shares come from different sharings, thresholds or fields"
+                )
+            }
+            Error::OutOfRange => write!(f, "value is not an element of the field"),
+            Error::MissingCommitments => write!(f, "no VSS commitments have been generated"),
+            Error::InconsistentShares => write!(
+                f,
+                "shares do not lie on a single polynomial of degree threshold - 1"
+            ),
+            Error::TooManyFaults { tolerated } => write!(
+                f,
+                "more than {} shares are corrupted, can't correct them",
+                tolerated
+            ),
+            Error::TooManySubsets { max } => write!(
+                f,
+                "more than {} subsets to search for consistent shares",
+                max
+            ),
+            Error::InvalidShares(positions) => {
+                write!(
+                    f,
+                    "shares at positions {:?} fail VSS verification",
+                    positions
+                )
+            }
+            Error::CommitmentMismatch => {
+                write!(f, "reconstructed secret does not match the VSS commitments")
+            }
+            Error::MissingBlinding => write!(f, "share has no Pedersen blinding value"),
+            Error::InvalidParams(reason) => write!(f, "invalid parameters: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
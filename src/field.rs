@@ -0,0 +1,631 @@
+use std::fmt::Debug;
+use std::sync::atomic::{compiler_fence, Ordering};
+
+use num_bigint::{BigInt, RandBigInt, Sign};
+use num_traits::{One, Zero};
+use rand::prelude::*;
+use rand::rngs::OsRng;
+use zeroize::Zeroize;
+
+use crate::error::{Error, Result};
+use crate::gf256;
+use crate::primes;
+
+// Overwrites a (possibly secret) value with zeros in place
+pub trait Wipe {
+    fn wipe(&mut self);
+}
+
+impl Wipe for u8 {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl Wipe for u128 {
+    fn wipe(&mut self) {
+        self.zeroize();
+    }
+}
+
+// num-bigint has no zeroize support, but assigning an all-zero slice of
+// the same length writes over the existing digit buffer before it is
+// normalised away. Temporaries allocated inside num-bigint's own
+// arithmetic are out of reach, so this is best effort.
+impl Wipe for BigInt {
+    fn wipe(&mut self) {
+        let digits = self.magnitude().iter_u32_digits().len();
+        self.assign_from_slice(Sign::Plus, &vec![0u32; digits]);
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl<T: Wipe> Wipe for Vec<T> {
+    fn wipe(&mut self) {
+        self.iter_mut().for_each(Wipe::wipe);
+        self.clear();
+    }
+}
+
+// Replaces `target` with `next`, wiping the old value first
+pub fn replace_wiped<T: Wipe>(target: &mut T, next: T) {
+    target.wipe();
+    *target = next;
+}
+
+// A finite field the polynomial and Lagrange code can run over.
+// The field value itself carries whatever context the arithmetic needs
+// (e.g. the modulus), elements are plain values.
+pub trait Field: Clone + Debug {
+    type Elem: Clone + Debug + PartialEq + Wipe;
+
+    // Identifies the field (and its modulus) so shares from different
+    // fields are never combined
+    fn id(&self) -> String;
+    // Number of elements
+    fn order(&self) -> BigInt;
+    fn zero(&self) -> Self::Elem;
+    fn one(&self) -> Self::Elem;
+    fn add(&self, a: &Self::Elem, b: &Self::Elem) -> Self::Elem;
+    fn sub(&self, a: &Self::Elem, b: &Self::Elem) -> Self::Elem;
+    fn mul(&self, a: &Self::Elem, b: &Self::Elem) -> Self::Elem;
+    // None for zero
+    fn inverse(&self, a: &Self::Elem) -> Option<Self::Elem>;
+    // Uniform over the whole field
+    fn random<R: RngCore + CryptoRng + ?Sized>(&self, rng: &mut R) -> Self::Elem;
+    // Small integers, used for the default share x-coordinates
+    fn elem_from_u64(&self, value: u64) -> Self::Elem;
+    // Fixed-length big-endian encoding
+    fn elem_to_bytes(&self, a: &Self::Elem) -> Vec<u8>;
+    // None if the bytes have the wrong length or don't encode an element
+    fn elem_from_bytes(&self, bytes: &[u8]) -> Option<Self::Elem>;
+
+    fn is_zero(&self, a: &Self::Elem) -> bool {
+        *a == self.zero()
+    }
+
+    // Whether `a` is a canonical element, only needed where the element
+    // type can hold values outside the field
+    fn contains(&self, _a: &Self::Elem) -> bool {
+        true
+    }
+
+    // The element as an integer, e.g. an exponent for VSS commitments.
+    // The encoding is wiped, the result is up to the caller.
+    fn to_bigint(&self, a: &Self::Elem) -> BigInt {
+        let mut bytes = self.elem_to_bytes(a);
+        let result = BigInt::from_bytes_be(Sign::Plus, &bytes);
+        bytes.zeroize();
+        result
+    }
+}
+
+// GF(2^8) with the AES polynomial, compact one-byte shares
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gf256;
+
+impl Field for Gf256 {
+    type Elem = u8;
+
+    fn id(&self) -> String {
+        "gf256".to_string()
+    }
+
+    fn order(&self) -> BigInt {
+        BigInt::from(256)
+    }
+
+    fn zero(&self) -> u8 {
+        0
+    }
+
+    fn one(&self) -> u8 {
+        1
+    }
+
+    fn add(&self, a: &u8, b: &u8) -> u8 {
+        gf256::add(*a, *b)
+    }
+
+    fn sub(&self, a: &u8, b: &u8) -> u8 {
+        gf256::add(*a, *b)
+    }
+
+    fn mul(&self, a: &u8, b: &u8) -> u8 {
+        gf256::mul(*a, *b)
+    }
+
+    fn inverse(&self, a: &u8) -> Option<u8> {
+        (*a != 0).then(|| gf256::inverse(*a))
+    }
+
+    fn random<R: RngCore + CryptoRng + ?Sized>(&self, rng: &mut R) -> u8 {
+        rng.gen()
+    }
+
+    fn elem_from_u64(&self, value: u64) -> u8 {
+        value as u8
+    }
+
+    fn elem_to_bytes(&self, a: &u8) -> Vec<u8> {
+        vec![*a]
+    }
+
+    fn elem_from_bytes(&self, bytes: &[u8]) -> Option<u8> {
+        match bytes {
+            [byte] => Some(*byte),
+            _ => None,
+        }
+    }
+}
+
+// Prime field for odd primes below 2^64, elements kept in Montgomery
+// form (a * 2^64 mod p) so multiplication needs no division
+#[derive(Debug, Clone, Copy)]
+pub struct Fp64 {
+    modulus: u64,
+    // -p^-1 mod 2^64
+    inv: u64,
+    // 2^128 mod p, converts into Montgomery form
+    r2: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fp64Elem(u64);
+
+impl Wipe for Fp64Elem {
+    fn wipe(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Fp64 {
+    // Panics unless `modulus` is an odd prime, `try_new` is the
+    // fallible version for moduli that come from input
+    pub fn new(modulus: u64) -> Self {
+        Self::try_new(modulus).expect("Modulus must be an odd prime")
+    }
+
+    // Inverses come from Fermat's little theorem, which silently gives
+    // wrong answers for a composite modulus, so the modulus is tested.
+    // Baillie-PSW has no counterexamples below 2^64, this is cheap.
+    pub fn try_new(modulus: u64) -> Result<Self> {
+        if modulus <= 2
+            || modulus & 1 == 0
+            || !primes::is_probable_prime(&BigInt::from(modulus), &mut OsRng)
+        {
+            return Err(Error::InvalidParams("field modulus must be an odd prime"));
+        }
+
+        // Newton iteration for p^-1 mod 2^64, each step doubles the correct bits
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(modulus.wrapping_mul(inv)));
+        }
+        let r2 = (u128::MAX % modulus as u128 + 1) % modulus as u128;
+
+        Ok(Self {
+            modulus,
+            inv: inv.wrapping_neg(),
+            r2: r2 as u64,
+        })
+    }
+
+    // Largest prime below 2^64
+    pub fn default_prime() -> Self {
+        Self::new(u64::MAX - 58)
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    // Computes t * 2^-64 mod p for t < p * 2^64
+    fn reduce(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.inv);
+        let (sum, carry) = t.overflowing_add(m as u128 * self.modulus as u128);
+        let mut u = (sum >> 64) | ((carry as u128) << 64);
+        if u >= self.modulus as u128 {
+            u -= self.modulus as u128;
+        }
+        u as u64
+    }
+
+    pub fn montgomery_form(&self, value: u64) -> Fp64Elem {
+        Fp64Elem(self.reduce((value % self.modulus) as u128 * self.r2 as u128))
+    }
+
+    pub fn canonical(&self, a: &Fp64Elem) -> u64 {
+        self.reduce(a.0 as u128)
+    }
+
+    fn pow(&self, a: &Fp64Elem, mut exp: u64) -> Fp64Elem {
+        let mut result = self.one();
+        let mut base = *a;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = self.mul(&result, &base);
+            }
+            base = self.mul(&base, &base);
+            exp >>= 1;
+        }
+        result
+    }
+}
+
+impl Field for Fp64 {
+    type Elem = Fp64Elem;
+
+    fn id(&self) -> String {
+        format!("fp64:{:x}", self.modulus)
+    }
+
+    fn order(&self) -> BigInt {
+        BigInt::from(self.modulus)
+    }
+
+    fn zero(&self) -> Fp64Elem {
+        Fp64Elem(0)
+    }
+
+    fn one(&self) -> Fp64Elem {
+        self.montgomery_form(1)
+    }
+
+    fn add(&self, a: &Fp64Elem, b: &Fp64Elem) -> Fp64Elem {
+        let sum = a.0 as u128 + b.0 as u128;
+        Fp64Elem((sum % self.modulus as u128) as u64)
+    }
+
+    fn sub(&self, a: &Fp64Elem, b: &Fp64Elem) -> Fp64Elem {
+        if a.0 >= b.0 {
+            Fp64Elem(a.0 - b.0)
+        } else {
+            Fp64Elem(self.modulus - (b.0 - a.0))
+        }
+    }
+
+    fn mul(&self, a: &Fp64Elem, b: &Fp64Elem) -> Fp64Elem {
+        Fp64Elem(self.reduce(a.0 as u128 * b.0 as u128))
+    }
+
+    // Fermat's little theorem: a^(p-2) = a^-1 mod p
+    fn inverse(&self, a: &Fp64Elem) -> Option<Fp64Elem> {
+        (a.0 != 0).then(|| self.pow(a, self.modulus - 2))
+    }
+
+    fn random<R: RngCore + CryptoRng + ?Sized>(&self, rng: &mut R) -> Fp64Elem {
+        self.montgomery_form(rng.gen_range(0..self.modulus))
+    }
+
+    fn elem_from_u64(&self, value: u64) -> Fp64Elem {
+        self.montgomery_form(value)
+    }
+
+    fn elem_to_bytes(&self, a: &Fp64Elem) -> Vec<u8> {
+        self.canonical(a).to_be_bytes().to_vec()
+    }
+
+    fn elem_from_bytes(&self, bytes: &[u8]) -> Option<Fp64Elem> {
+        let value = u64::from_be_bytes(bytes.try_into().ok()?);
+        (value < self.modulus).then(|| self.montgomery_form(value))
+    }
+}
+
+// Exponents of the Mersenne primes 2^e - 1 used as default field moduli.
+// The smallest one that is larger than the secret gets picked.
+const MERSENNE_EXPONENTS: [u32; 12] = [
+    61, 89, 107, 127, 521, 607, 1279, 2203, 2281, 3217, 4253, 4423,
+];
+
+// Prime field of arbitrary size, e.g. Z_q for the VSS group order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrimeField {
+    modulus: BigInt,
+}
+
+impl PrimeField {
+    // For moduli already known to be prime, like the built-in Mersenne
+    // primes or the order of a validated group. Panics below 2 but skips
+    // the primality test, which takes a while for large moduli.
+    pub fn new(modulus: BigInt) -> Self {
+        assert!(modulus > BigInt::one(), "Modulus must be a prime");
+        Self { modulus }
+    }
+
+    // For moduli that come from input: Fermat inverses silently give
+    // wrong answers for a composite modulus, so it is tested for primality
+    pub fn try_new(modulus: BigInt) -> Result<Self> {
+        if modulus <= BigInt::one() || !primes::is_probable_prime(&modulus, &mut OsRng) {
+            return Err(Error::InvalidParams("field modulus must be a prime"));
+        }
+        Ok(Self { modulus })
+    }
+
+    // Smallest built-in Mersenne prime field that can hold `secret`
+    pub fn for_secret(secret: &BigInt) -> Result<Self> {
+        let exponent = MERSENNE_EXPONENTS
+            .iter()
+            .copied()
+            .find(|&e| secret.bits() < e as u64)
+            .ok_or(Error::OutOfRange)?;
+        Ok(Self::new((BigInt::one() << exponent) - 1))
+    }
+
+    pub fn modulus(&self) -> &BigInt {
+        &self.modulus
+    }
+
+    pub fn reduce(&self, value: &BigInt) -> BigInt {
+        let value = value % &self.modulus;
+        if value.sign() == Sign::Minus {
+            value + &self.modulus
+        } else {
+            value
+        }
+    }
+
+    fn byte_len(&self) -> usize {
+        self.modulus.bits().div_ceil(8) as usize
+    }
+}
+
+impl Field for PrimeField {
+    type Elem = BigInt;
+
+    fn id(&self) -> String {
+        format!("fp:{:x}", self.modulus)
+    }
+
+    fn order(&self) -> BigInt {
+        self.modulus.clone()
+    }
+
+    fn contains(&self, a: &BigInt) -> bool {
+        a.sign() != Sign::Minus && *a < self.modulus
+    }
+
+    fn zero(&self) -> BigInt {
+        BigInt::zero()
+    }
+
+    fn one(&self) -> BigInt {
+        BigInt::one()
+    }
+
+    fn add(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        (a + b) % &self.modulus
+    }
+
+    fn sub(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        self.reduce(&(a - b))
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        a * b % &self.modulus
+    }
+
+    // Fermat's little theorem: a^(p-2) = a^-1 mod p
+    fn inverse(&self, a: &BigInt) -> Option<BigInt> {
+        (!a.is_zero()).then(|| a.modpow(&(&self.modulus - 2), &self.modulus))
+    }
+
+    fn random<R: RngCore + CryptoRng + ?Sized>(&self, rng: &mut R) -> BigInt {
+        rng.gen_bigint_range(&BigInt::zero(), &self.modulus)
+    }
+
+    fn elem_from_u64(&self, value: u64) -> BigInt {
+        self.reduce(&BigInt::from(value))
+    }
+
+    fn elem_to_bytes(&self, a: &BigInt) -> Vec<u8> {
+        let (_, bytes) = a.to_bytes_be();
+        let mut padded = vec![0u8; self.byte_len() - bytes.len()];
+        padded.extend_from_slice(&bytes);
+        padded
+    }
+
+    fn elem_from_bytes(&self, bytes: &[u8]) -> Option<BigInt> {
+        if bytes.len() != self.byte_len() {
+            return None;
+        }
+        let value = BigInt::from_bytes_be(Sign::Plus, bytes);
+        (value < self.modulus).then_some(value)
+    }
+}
+
+// GF(2^128) with the reduction polynomial x^128 + x^7 + x^2 + x + 1,
+// bit i of the u128 is the coefficient of x^i
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Gf2_128;
+
+impl Gf2_128 {
+    // Low 128 bits of x^128 mod the reduction polynomial
+    const REDUCTION: u128 = 0x87;
+}
+
+impl Field for Gf2_128 {
+    type Elem = u128;
+
+    fn id(&self) -> String {
+        "gf2_128".to_string()
+    }
+
+    fn order(&self) -> BigInt {
+        BigInt::one() << 128
+    }
+
+    fn zero(&self) -> u128 {
+        0
+    }
+
+    fn one(&self) -> u128 {
+        1
+    }
+
+    fn add(&self, a: &u128, b: &u128) -> u128 {
+        a ^ b
+    }
+
+    fn sub(&self, a: &u128, b: &u128) -> u128 {
+        a ^ b
+    }
+
+    // Same branch-free shift-and-add as gf256::mul
+    fn mul(&self, a: &u128, b: &u128) -> u128 {
+        let (mut a, mut b) = (*a, *b);
+        let mut product = 0u128;
+        for _ in 0..128 {
+            product ^= a & 0u128.wrapping_sub(b & 1);
+            let carry = 0u128.wrapping_sub(a >> 127);
+            a = (a << 1) ^ (Self::REDUCTION & carry);
+            b >>= 1;
+        }
+        product
+    }
+
+    // a^(2^128 - 2) = a^-1, the multiplicative group has order 2^128 - 1
+    fn inverse(&self, a: &u128) -> Option<u128> {
+        if *a == 0 {
+            return None;
+        }
+        // 2^128 - 2 is 127 ones followed by a zero
+        let mut result = 1u128;
+        let mut base = *a;
+        for _ in 0..127 {
+            base = self.mul(&base, &base);
+            result = self.mul(&result, &base);
+        }
+        Some(result)
+    }
+
+    fn random<R: RngCore + CryptoRng + ?Sized>(&self, rng: &mut R) -> u128 {
+        rng.gen()
+    }
+
+    fn elem_from_u64(&self, value: u64) -> u128 {
+        value as u128
+    }
+
+    fn elem_to_bytes(&self, a: &u128) -> Vec<u8> {
+        a.to_be_bytes().to_vec()
+    }
+
+    fn elem_from_bytes(&self, bytes: &[u8]) -> Option<u128> {
+        Some(u128::from_be_bytes(bytes.try_into().ok()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Largest primes below 2^2, 2^32 and 2^64, plus a Fermat and a
+    // Mersenne prime
+    const FP64_MODULI: [u64; 5] = [3, 65537, 4294967291, (1 << 61) - 1, 18446744073709551557];
+
+    #[test]
+    fn fp64_matches_bigint_arithmetic() {
+        for modulus in FP64_MODULI {
+            let field = Fp64::new(modulus);
+            let p = BigInt::from(modulus);
+            let mut values = vec![0, 1, 2, modulus - 2, modulus - 1];
+            values.extend((0..20).map(|_| OsRng.gen_range(0..modulus)));
+
+            for &a in &values {
+                let x = field.elem_from_u64(a);
+                assert_eq!(field.canonical(&x), a);
+                assert_eq!(field.elem_from_bytes(&field.elem_to_bytes(&x)), Some(x));
+
+                for &b in &values {
+                    let y = field.elem_from_u64(b);
+                    let (a, b) = (BigInt::from(a), BigInt::from(b));
+                    let expected = |value: BigInt| ((value % &p) + &p) % &p;
+                    let canonical = |elem: Fp64Elem| BigInt::from(field.canonical(&elem));
+
+                    assert_eq!(canonical(field.add(&x, &y)), expected(&a + &b));
+                    assert_eq!(canonical(field.sub(&x, &y)), expected(&a - &b));
+                    assert_eq!(canonical(field.mul(&x, &y)), expected(&a * &b));
+                }
+
+                match field.inverse(&x) {
+                    None => assert_eq!(a, 0),
+                    Some(inverse) => {
+                        assert_eq!(field.mul(&x, &inverse), field.one());
+                        let expected = BigInt::from(a).modpow(&(&p - 2), &p);
+                        assert_eq!(BigInt::from(field.canonical(&inverse)), expected);
+                    }
+                }
+            }
+            assert_eq!(field.elem_from_bytes(&modulus.to_be_bytes()), None);
+        }
+    }
+
+    #[test]
+    fn try_new_rejects_bad_moduli() {
+        // 15 used to pass and reconstruct the secret 5 as 14, 561 is a
+        // Carmichael number
+        for modulus in [0, 1, 2, 4, 15, 561, 65535, 65536] {
+            assert_eq!(
+                Fp64::try_new(modulus).map(|field| field.modulus()),
+                Err(Error::InvalidParams("field modulus must be an odd prime"))
+            );
+        }
+        for modulus in [-7, 0, 1, 15, 561] {
+            assert!(PrimeField::try_new(BigInt::from(modulus)).is_err());
+        }
+    }
+
+    #[test]
+    fn gf256_inverses() {
+        let field = Gf256;
+        assert_eq!(field.inverse(&0), None);
+        for a in 1..=255u8 {
+            assert_eq!(field.mul(&a, &field.inverse(&a).unwrap()), 1);
+        }
+        // The AES field: {53} * {ca} = {01}
+        assert_eq!(field.mul(&0x53, &0xca), 1);
+    }
+
+    #[test]
+    fn gf2_128_inverses() {
+        let field = Gf2_128;
+        assert_eq!(field.inverse(&0), None);
+        let mut values = vec![1, 2, 0x87, 1 << 127, u128::MAX];
+        values.extend((0..20).map(|_| field.random(&mut OsRng)));
+        for a in values {
+            let inverse = field.inverse(&a).unwrap();
+            assert_eq!(field.mul(&a, &inverse), 1);
+            assert_eq!(field.mul(&inverse, &a), 1);
+        }
+        // x^127 * x = x^128 = x^7 + x^2 + x + 1
+        assert_eq!(field.mul(&(1 << 127), &2), 0x87);
+    }
+
+    #[test]
+    fn prime_field_inverses() {
+        let field = PrimeField::new((BigInt::one() << 127) - 1);
+        for _ in 0..20 {
+            let a = field.random(&mut OsRng);
+            if let Some(inverse) = field.inverse(&a) {
+                assert_eq!(field.mul(&a, &inverse), BigInt::one());
+            }
+        }
+    }
+
+    #[test]
+    fn wipe_zeroes_values() {
+        let mut big: BigInt = (BigInt::one() << 200) + 12345;
+        big.wipe();
+        assert!(big.is_zero());
+
+        let field = Fp64::default_prime();
+        let mut elem = field.elem_from_u64(99);
+        elem.wipe();
+        assert_eq!(elem, field.zero());
+
+        let mut values = vec![0xffu8; 16];
+        values.wipe();
+        assert!(values.is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+use rand::prelude::*;
+
+use std::fmt;
+
+use crate::error::{Error, Result};
+use crate::field::{Field, Gf256, Wipe};
+use crate::polynomial::{self, Polynomial};
+use crate::share::{self, SetId, Share};
+
+// Byte-wise Shamir sharing over GF(2^8), compatible in spirit with `ssss`
+// and Vault's unseal keys: every byte of the secret gets its own random
+// polynomial and every share is a byte vector as long as the secret.
+
+// AES reduction polynomial x^8 + x^4 + x^3 + x + 1
+const REDUCTION: u8 = 0x1b;
+
+pub fn add(a: u8, b: u8) -> u8 {
+    a ^ b
+}
+
+// Carry-less multiply without lookup tables so the running time
+// doesn't depend on the (secret) operands
+pub fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    for _ in 0..8 {
+        product ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (REDUCTION & carry);
+        b >>= 1;
+    }
+    product
+}
+
+// a^254 = a^-1 since the multiplicative group has order 255
+pub fn inverse(a: u8) -> u8 {
+    assert!(a != 0, "Zero has no inverse in GF(256)");
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exp = 254u8;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+// One participant's byte-mode share: a value byte per secret byte, all
+// at the same x-coordinate, with the metadata of any other share
+pub type ByteShare = Share<Gf256, Vec<u8>>;
+
+// A secret recovered by `combine_shares`, wiped on drop and redacted in
+// Debug like the shares it came from
+pub struct ByteSecret(Vec<u8>);
+
+impl ByteSecret {
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for ByteSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ByteSecret").field(&"<redacted>").finish()
+    }
+}
+
+impl Drop for ByteSecret {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
+// Split `secret` into `shares` byte vectors, any `threshold` of which
+// recover it. Share x-coordinates run from 1 to `shares`.
+pub fn split_secret<R: RngCore + CryptoRng + ?Sized>(
+    secret: &[u8],
+    shares: usize,
+    threshold: usize,
+    rng: &mut R,
+) -> Result<Vec<ByteShare>> {
+    if threshold == 0 || threshold > shares {
+        return Err(Error::InvalidThreshold { threshold, shares });
+    }
+    if shares > 255 {
+        return Err(Error::TooManyShares { shares, max: 255 });
+    }
+
+    // Each polynomial wipes its coefficients when dropped
+    let mut values = vec![Vec::with_capacity(secret.len()); shares];
+    for &byte in secret {
+        let polynomial = Polynomial::random(Gf256, byte, threshold - 1, rng);
+        for (x, value) in (1..=shares as u8).zip(values.iter_mut()) {
+            value.push(polynomial.evaluate(&x));
+        }
+    }
+
+    let set_id = SetId::random(rng);
+    Ok((1..=shares as u8)
+        .zip(values)
+        .map(|(x, value)| Share::new(x, value, set_id, threshold, Gf256.id()))
+        .collect())
+}
+
+// Lagrange interpolation at x = 0, byte by byte. Needs at least the
+// threshold of shares, all from the same split.
+pub fn combine_shares(shares: &[ByteShare]) -> Result<ByteSecret> {
+    share::check_metadata(&Gf256, shares)?;
+    let length = shares[0].expose_secret().len();
+    if shares
+        .iter()
+        .any(|share| share.expose_secret().len() != length)
+    {
+        return Err(Error::MismatchedShares);
+    }
+
+    // The basis values only depend on the x-coordinates, so compute them once
+    let x_values: Vec<u8> = shares.iter().map(|share| share.index).collect();
+    let basis = polynomial::lagrange_coefficients(&Gf256, &x_values, &0)?;
+
+    let secret = (0..length)
+        .map(|position| {
+            shares.iter().zip(&basis).fold(0, |acc, (share, &b)| {
+                add(acc, mul(share.expose_secret()[position], b))
+            })
+        })
+        .collect();
+    Ok(ByteSecret(secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn split_and_combine() {
+        let shares = split_secret(b"correct horse", 5, 3, &mut OsRng).unwrap();
+        for quorum in [&shares[..3], &shares[2..], &shares[..]] {
+            let secret = combine_shares(quorum).unwrap();
+            assert_eq!(secret.expose_secret(), b"correct horse");
+        }
+    }
+
+    #[test]
+    fn rejects_too_few_or_mixed_shares() {
+        let shares = split_secret(b"secret", 5, 3, &mut OsRng).unwrap();
+        assert_eq!(
+            combine_shares(&shares[..2]).err(),
+            Some(Error::InsufficientShares { needed: 3, got: 2 })
+        );
+
+        let other = split_secret(b"secret", 5, 3, &mut OsRng).unwrap();
+        let mixed = [shares[0].clone(), shares[1].clone(), other[2].clone()];
+        assert_eq!(combine_shares(&mixed).err(), Some(Error::MismatchedShares));
+
+        let repeated = [shares[0].clone(), shares[1].clone(), shares[1].clone()];
+        assert_eq!(combine_shares(&repeated).err(), Some(Error::DuplicateIndex));
+
+        let mut truncated = shares[..3].to_vec();
+        let short = shares[1].expose_secret()[1..].to_vec();
+        truncated[1] = Share::new(2, short, shares[1].set_id, 3, Gf256.id());
+        assert_eq!(
+            combine_shares(&truncated).err(),
+            Some(Error::MismatchedShares)
+        );
+
+        assert_eq!(
+            split_secret(b"secret", 256, 3, &mut OsRng).unwrap_err(),
+            Error::TooManyShares {
+                shares: 256,
+                max: 255
+            }
+        );
+    }
+
+    #[test]
+    fn debug_redacts_bytes() {
+        let shares = split_secret(b"hunter2", 3, 2, &mut OsRng).unwrap();
+        let secret = combine_shares(&shares[1..]).unwrap();
+        assert_eq!(format!("{:?}", secret), "ByteSecret(\"<redacted>\")");
+
+        for share in &shares {
+            let debug = format!("{:?}", share);
+            assert!(debug.contains("<redacted>"));
+            // Debug of a byte vector would list its bytes
+            let bytes = format!("{:?}", share.expose_secret());
+            assert!(!debug.contains(&bytes[1..bytes.len() - 1]));
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use sha2::{Digest, Sha256};
+
+// `length` bytes from SHA-256(domain || counter || block || message)
+// for block = 0, 1, ..., cut to size. Callers that reject some outputs
+// retry with the next counter, so each attempt is a fresh expansion.
+pub fn expand(domain: &[u8], counter: u32, message: &[u8], length: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(length);
+    let mut block = 0u32;
+    while bytes.len() < length {
+        let digest = Sha256::new()
+            .chain_update(domain)
+            .chain_update(counter.to_be_bytes())
+            .chain_update(block.to_be_bytes())
+            .chain_update(message)
+            .finalize();
+        bytes.extend_from_slice(&digest);
+        block += 1;
+    }
+    bytes.truncate(length);
+    bytes
+}
@@ -1,41 +1,148 @@
 #![allow(unused, dead_code)]
+mod curves;
+mod error;
+mod field;
+mod gf256;
+mod groups;
+mod hash;
+mod multiexp;
+mod pedersen;
+mod polynomial;
+mod primes;
+mod reconstructor;
+mod robust;
 mod shamir;
+mod share;
 mod vss;
 
+use curves::{Ristretto255, Secp256k1};
+use num_bigint::BigInt;
+use rand::rngs::OsRng;
 use shamir::SharmirModel;
 use std::env;
+use std::error::Error;
+use vss::{Group, VSSParams};
 
 // How to run -> cargo run args
 // -q for silent mode 143 - secret_number 5 - num_of_shares 2 - threshold
+// Byte mode -> cargo run -- --bytes "my password" 5
+// VSS group generation -> cargo run --release -- --vss-params 2048
+// Curve commitments -> cargo run -- --curve ristretto255 143 5
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() < 3 {
-        eprintln!("Please give all the args... (secret, shares, threshold)");
+    let result = if args.len() > 1 && args[1] == "--bytes" {
+        run_bytes(&args[2..])
+    } else if args.len() > 1 && args[1] == "--vss-params" {
+        run_vss_params(&args[2..])
+    } else if args.len() > 1 && args[1] == "--curve" {
+        run_curve(&args[2..])
+    } else {
+        run(&args[1..])
+    };
+
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
         std::process::exit(1);
     }
+}
 
-    let secret: i64 = args[1].parse().expect("Secret must be an integer");
-    let shares: usize = args[2].parse().expect("Shares must be an integer");
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    if args.len() < 2 {
+        return Err("Please give all the args... (secret, shares, threshold)".into());
+    }
 
-    let mut s = SharmirModel::new(secret, shares, 3);
-    let mut m = s.clone();
+    let secret: BigInt = args[0].parse().map_err(|_| "Secret must be an integer")?;
+    let shares: usize = args[1].parse().map_err(|_| "Shares must be an integer")?;
 
-    s.generate_shares();
-    let generated_shares = s.get_shares().clone();
+    share_and_verify(secret, shares, VSSParams::modp_2048())
+}
+
+fn run_curve(args: &[String]) -> Result<(), Box<dyn Error>> {
+    if args.len() < 3 {
+        return Err("Please give all the args... (--curve name, secret, shares)".into());
+    }
+
+    let secret: BigInt = args[1].parse().map_err(|_| "Secret must be an integer")?;
+    let shares: usize = args[2].parse().map_err(|_| "Shares must be an integer")?;
+
+    match args[0].as_str() {
+        "ristretto255" => share_and_verify(secret, shares, Ristretto255),
+        "secp256k1" => share_and_verify(secret, shares, Secp256k1),
+        _ => Err("Curve must be ristretto255 or secp256k1".into()),
+    }
+}
 
-    let sum = m.construct_polynomial(1);
-    println!("Polynomial value sum at x=1: {}", sum);
+fn share_and_verify<G: Group>(
+    secret: BigInt,
+    shares: usize,
+    group: G,
+) -> Result<(), Box<dyn Error>> {
+    let mut s = SharmirModel::with_vss(secret, shares, 3.min(shares), group)?;
 
+    s.generate_shares(&mut OsRng);
+    let generated_shares = s.get_shares().clone();
+
+    println!("Model: {:?}", s);
     println!("Generated shares: {:?}", generated_shares);
 
     // Verify each share
-    for &(x, share) in &generated_shares {
-        let is_valid = s.verify_share(x, share);
-        println!("Share ({}, {}) is valid: {}", x, share, is_valid);
+    for share in &generated_shares {
+        let is_valid = s.verify_share(share)?;
+        println!(
+            "Share ({}, {}) is valid: {}",
+            share.index,
+            share.expose_secret(),
+            is_valid
+        );
     }
 
-    let reconstructed_secret = m.reconstruct_secret(&generated_shares);
+    let reconstructed_secret = s.reconstruct_secret(&generated_shares)?;
     println!("Reconstructed secret: {}", reconstructed_secret);
+    Ok(())
+}
+
+fn run_bytes(args: &[String]) -> Result<(), Box<dyn Error>> {
+    if args.len() < 2 {
+        return Err("Please give all the args... (--bytes secret, shares)".into());
+    }
+
+    let secret = args[0].as_bytes();
+    let shares: usize = args[1].parse().map_err(|_| "Shares must be an integer")?;
+    let threshold = 3.min(shares);
+
+    let generated_shares = gf256::split_secret(secret, shares, threshold, &mut OsRng)?;
+    for share in &generated_shares {
+        let hex: String = share
+            .expose_secret()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        println!("Share {}: {}", share.index, hex);
+    }
+
+    let reconstructed = gf256::combine_shares(&generated_shares[..threshold])?;
+    println!(
+        "Reconstructed secret: {}",
+        String::from_utf8_lossy(reconstructed.expose_secret())
+    );
+    Ok(())
+}
+
+fn run_vss_params(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let bits: u64 = match args.first() {
+        Some(bits) => bits.parse().map_err(|_| "Bits must be an integer")?,
+        None => 2048,
+    };
+
+    let params = VSSParams::generate(bits, &mut OsRng)?;
+    println!("p = {:x}", params.p());
+    println!("q = {:x}", params.q());
+    println!("g = {:x}", params.g());
+    match params.validate() {
+        Ok(()) => println!("Meets the default security policy"),
+        Err(err) => println!("Not for production: {}", err),
+    }
+    Ok(())
 }
@@ -0,0 +1,264 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+
+use crate::vss::{self, Group};
+
+// Multi-exponentiation prod P_i^(k_i) and fixed-base exponentiation over
+// any `Group`, written with `add` only so every backend gets them. Both
+// are variable time, just like num-bigint's modpow they replace.
+
+// Below this many points Straus beats Pippenger
+const PIPPENGER_THRESHOLD: usize = 32;
+
+// Straus window width
+const STRAUS_WINDOW: u64 = 4;
+
+// Window width for the generator table of `VSSParams`
+pub const GENERATOR_WINDOW: u64 = 4;
+
+// Picks Straus for a few points and Pippenger for many. Scalars are
+// reduced mod the group order, extra points or scalars are ignored.
+pub fn multi_exp<G: Group>(group: &G, points: &[G::Point], scalars: &[BigInt]) -> G::Point {
+    let order = group.order();
+    let scalars: Vec<BigInt> = scalars
+        .iter()
+        .map(|scalar| vss::reduce(scalar, &order))
+        .collect();
+    let count = points.len().min(scalars.len());
+
+    if count < PIPPENGER_THRESHOLD {
+        straus(group, &points[..count], &scalars[..count])
+    } else {
+        pippenger(group, &points[..count], &scalars[..count])
+    }
+}
+
+// Interleaved window method: one shared run of doublings, plus one
+// table lookup per point and window. Expects reduced scalars.
+pub fn straus<G: Group>(group: &G, points: &[G::Point], scalars: &[BigInt]) -> G::Point {
+    let bits = scalars.iter().map(BigInt::bits).max().unwrap_or(0);
+    let windows = bits.div_ceil(STRAUS_WINDOW);
+
+    // tables[i][d] = P_i^d for every digit d
+    let tables: Vec<Vec<G::Point>> = points
+        .iter()
+        .map(|point| powers(group, point, 1 << STRAUS_WINDOW))
+        .collect();
+
+    let mut result = group.identity();
+    for window in (0..windows).rev() {
+        for _ in 0..STRAUS_WINDOW {
+            result = group.add(&result, &result);
+        }
+        for (table, scalar) in tables.iter().zip(scalars) {
+            let digit = digit(scalar, window * STRAUS_WINDOW, STRAUS_WINDOW);
+            if digit != 0 {
+                result = group.add(&result, &table[digit]);
+            }
+        }
+    }
+    result
+}
+
+// Bucket method: per window, every point is added once into the bucket
+// of its digit and the buckets are summed with running sums. Cost per
+// window is about n + 2^(c+1) group operations instead of n * 2^c.
+// Expects reduced scalars.
+pub fn pippenger<G: Group>(group: &G, points: &[G::Point], scalars: &[BigInt]) -> G::Point {
+    let bits = scalars.iter().map(BigInt::bits).max().unwrap_or(0);
+    let width = pippenger_window(points.len());
+    let windows = bits.div_ceil(width);
+
+    let mut result = group.identity();
+    for window in (0..windows).rev() {
+        for _ in 0..width {
+            result = group.add(&result, &result);
+        }
+
+        let mut buckets: Vec<Option<G::Point>> = vec![None; (1 << width) - 1];
+        for (point, scalar) in points.iter().zip(scalars) {
+            let digit = digit(scalar, window * width, width);
+            if digit != 0 {
+                let bucket = &mut buckets[digit - 1];
+                *bucket = Some(match bucket.take() {
+                    Some(sum) => group.add(&sum, point),
+                    None => point.clone(),
+                });
+            }
+        }
+
+        // sum_d d * B_d as the sum of the suffix sums of the buckets
+        let mut running = group.identity();
+        let mut total = group.identity();
+        for bucket in buckets.iter().rev() {
+            if let Some(sum) = bucket {
+                running = group.add(&running, sum);
+            }
+            total = group.add(&total, &running);
+        }
+        result = group.add(&result, &total);
+    }
+    result
+}
+
+// Roughly log2(n), the usual sweet spot for the bucket width
+fn pippenger_window(count: usize) -> u64 {
+    match count {
+        0..=31 => 3,
+        32..=127 => 5,
+        128..=1023 => 7,
+        1024..=8191 => 9,
+        _ => 11,
+    }
+}
+
+// P^0, P^1, ..., P^(count - 1)
+fn powers<G: Group>(group: &G, point: &G::Point, count: usize) -> Vec<G::Point> {
+    let mut table = Vec::with_capacity(count);
+    table.push(group.identity());
+    for i in 1..count {
+        table.push(group.add(&table[i - 1], point));
+    }
+    table
+}
+
+// Bits start..start + width of a non-negative scalar
+fn digit(scalar: &BigInt, start: u64, width: u64) -> usize {
+    (0..width)
+        .rev()
+        .fold(0, |acc, bit| (acc << 1) | scalar.bit(start + bit) as usize)
+}
+
+// Precomputed B^(d * 2^(w * i)) for every window i and digit d, so an
+// exponentiation of the fixed base B is one group operation per window
+// and no doublings at all. Worth it once the same base is raised many
+// times, like g in commitment generation and share verification.
+#[derive(Clone)]
+pub struct FixedBase<G: Group> {
+    window: u64,
+    table: Vec<Vec<G::Point>>,
+}
+
+impl<G: Group> FixedBase<G> {
+    // (2^window) * ceil(bits / window) points, e.g. 2 MiB for a 2048-bit
+    // MODP group with window 4
+    pub fn new(group: &G, base: &G::Point, window: u64) -> Self {
+        assert!((1..=16).contains(&window), "Window must be 1 to 16 bits");
+        let windows = group.order().bits().div_ceil(window);
+
+        let mut table = Vec::with_capacity(windows as usize);
+        let mut window_base = base.clone();
+        for _ in 0..windows {
+            let row = powers(group, &window_base, 1 << window);
+            // The next window's base is B^(2^w) of this one
+            window_base = group.add(&row[(1 << window) - 1], &window_base);
+            table.push(row);
+        }
+
+        Self { window, table }
+    }
+
+    pub fn mul(&self, group: &G, scalar: &BigInt) -> G::Point {
+        let scalar = vss::reduce(scalar, &group.order());
+        let mut result = group.identity();
+        for (i, row) in self.table.iter().enumerate() {
+            let digit = digit(&scalar, i as u64 * self.window, self.window);
+            if digit != 0 {
+                result = group.add(&result, &row[digit]);
+            }
+        }
+        result
+    }
+}
+
+// The table is large and public, only print its shape
+impl<G: Group> fmt::Debug for FixedBase<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBase")
+            .field("window", &self.window)
+            .field("windows", &self.table.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::Ristretto255;
+    use crate::vss::VSSParams;
+    use num_bigint::RandBigInt;
+    use rand::rngs::OsRng;
+
+    // Point counts on both sides of PIPPENGER_THRESHOLD
+    const COUNTS: [usize; 7] = [0, 1, 5, 31, 32, 33, 64];
+
+    fn naive<G: Group>(group: &G, points: &[G::Point], scalars: &[BigInt]) -> G::Point {
+        points
+            .iter()
+            .zip(scalars)
+            .fold(group.identity(), |acc, (point, scalar)| {
+                group.add(&acc, &group.mul(point, scalar))
+            })
+    }
+
+    fn check_against_naive<G: Group>(group: &G) {
+        let order = group.order();
+        for count in COUNTS {
+            let points: Vec<G::Point> = (0..count)
+                .map(|_| group.mul_generator(&OsRng.gen_bigint_range(&BigInt::from(1), &order)))
+                .collect();
+            // Unreduced and negative scalars must be reduced first
+            let mut scalars: Vec<BigInt> = (0..count)
+                .map(|_| OsRng.gen_bigint_range(&-&order, &(&order * 3)))
+                .collect();
+            if let Some(first) = scalars.first_mut() {
+                *first = BigInt::from(0);
+            }
+            let expected = naive(group, &points, &scalars);
+
+            assert_eq!(multi_exp(group, &points, &scalars), expected, "{}", count);
+
+            let reduced: Vec<BigInt> = scalars.iter().map(|s| vss::reduce(s, &order)).collect();
+            assert_eq!(straus(group, &points, &reduced), expected, "{}", count);
+            assert_eq!(pippenger(group, &points, &reduced), expected, "{}", count);
+        }
+    }
+
+    #[test]
+    fn multi_exp_matches_naive_product() {
+        check_against_naive(&VSSParams::new());
+        check_against_naive(&Ristretto255);
+    }
+
+    #[test]
+    fn multi_exp_ignores_extra_scalars() {
+        let group = VSSParams::new();
+        let points = vec![group.generator(); 3];
+        let scalars: Vec<BigInt> = (1..=5).map(BigInt::from).collect();
+        assert_eq!(
+            multi_exp(&group, &points, &scalars),
+            group.mul_generator(&BigInt::from(6))
+        );
+    }
+
+    #[test]
+    fn fixed_base_matches_mul() {
+        let group = Ristretto255;
+        let order = group.order();
+        let base = group.mul_generator(&BigInt::from(7));
+        for window in [1, 4, 8] {
+            let table = FixedBase::new(&group, &base, window);
+            for scalar in [
+                BigInt::from(0),
+                BigInt::from(1),
+                &order - 1u32,
+                &order + 5u32,
+            ] {
+                assert_eq!(table.mul(&group, &scalar), group.mul(&base, &scalar));
+            }
+            let scalar = OsRng.gen_bigint_range(&BigInt::from(0), &order);
+            assert_eq!(table.mul(&group, &scalar), group.mul(&base, &scalar));
+        }
+    }
+}
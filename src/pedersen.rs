@@ -0,0 +1,135 @@
+use num_bigint::BigInt;
+use num_traits::Zero;
+
+use crate::error::{Error, Result};
+use crate::vss::{self, Group};
+
+// Pedersen VSS: each coefficient a_i of the sharing polynomial is
+// committed as C_i = g^a_i h^b_i, where b is a random blinding
+// polynomial. Participant i gets the pair (s_i, t_i) = (a(i), b(i)).
+// Unlike Feldman's g^a_i, the commitments are independent of the secret
+// even against an unbounded adversary. Binding only holds as long as
+// nobody knows log_g h, which is why h is hashed rather than chosen.
+
+// Domain separation for the second generator
+const H_DOMAIN: &[u8] = b"shamir-pedersen-second-generator";
+
+#[derive(Debug, Clone)]
+pub struct PedersenCommitments<G: Group> {
+    commitments: Vec<G::Point>,
+    h: G::Point,
+}
+
+// The second generator h for `group`, the same for every dealer
+pub fn second_generator<G: Group>(group: &G) -> G::Point {
+    let mut message = H_DOMAIN.to_vec();
+    message.extend_from_slice(group.id().as_bytes());
+    group.hash_to_point(&message)
+}
+
+impl<G: Group> PedersenCommitments<G> {
+    // One blinding coefficient per sharing coefficient, both in Z_order
+    pub fn new(coefficients: &[BigInt], blinding: &[BigInt], group: &G) -> Result<Self> {
+        if coefficients.len() != blinding.len() {
+            return Err(Error::IndexCountMismatch {
+                expected: coefficients.len(),
+                got: blinding.len(),
+            });
+        }
+
+        let h = second_generator(group);
+        let commitments = coefficients
+            .iter()
+            .zip(blinding)
+            .map(|(a, b)| group.add(&group.mul_generator(a), &group.mul(&h, b)))
+            .collect();
+
+        Ok(Self { commitments, h })
+    }
+
+    // Commitments received from the dealer. h is always rederived, a
+    // dealer who picked it could open commitments to anything.
+    pub fn from_points(commitments: Vec<G::Point>, group: &G) -> Self {
+        Self {
+            commitments,
+            h: second_generator(group),
+        }
+    }
+
+    pub fn points(&self) -> &[G::Point] {
+        &self.commitments
+    }
+
+    pub fn h(&self) -> &G::Point {
+        &self.h
+    }
+
+    // Short hex tag of the commitment vector, safe to log
+    pub fn fingerprint(&self, group: &G) -> String {
+        vss::fingerprint(group, &self.commitments)
+    }
+
+    // Checks g^share h^blinding = prod C_i^(x^i), everything in Z_order.
+    // Refuses groups that fail `Group::validate`.
+    pub fn verify_share(
+        &self,
+        x: &BigInt,
+        share: &BigInt,
+        blinding: &BigInt,
+        group: &G,
+    ) -> Result<bool> {
+        group.validate()?;
+        let order = group.order();
+        let x = vss::reduce(x, &order);
+        if x.is_zero() {
+            return Ok(false);
+        }
+
+        let expected = vss::evaluate_in_exponent(group, &self.commitments, &x);
+        let actual = group.add(&group.mul_generator(share), &group.mul(&self.h, blinding));
+        Ok(expected == actual)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vss::VSSParams;
+
+    #[test]
+    fn verify_share_binds_the_blinding() {
+        let group = VSSParams::new();
+        // a(x) = 9 + 4x, b(x) = 2 + 13x
+        let commitments = PedersenCommitments::new(
+            &[9, 4].map(BigInt::from),
+            &[2, 13].map(BigInt::from),
+            &group,
+        )
+        .unwrap();
+
+        let x = BigInt::from(3);
+        let (share, blinding) = (BigInt::from(21), BigInt::from(41));
+        assert_eq!(
+            commitments.verify_share(&x, &share, &blinding, &group),
+            Ok(true)
+        );
+        // Same share value, but the blinding of another point
+        assert_eq!(
+            commitments.verify_share(&x, &share, &BigInt::from(28), &group),
+            Ok(false)
+        );
+        assert_eq!(
+            commitments.verify_share(&x, &BigInt::from(22), &blinding, &group),
+            Ok(false)
+        );
+
+        // A receiver rederives h and gets the same commitments
+        let received = PedersenCommitments::from_points(commitments.points().to_vec(), &group);
+        assert_eq!(received.h(), commitments.h());
+        assert_ne!(received.h(), &group.generator());
+        assert_eq!(
+            received.verify_share(&x, &share, &blinding, &group),
+            Ok(true)
+        );
+    }
+}
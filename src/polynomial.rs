@@ -0,0 +1,375 @@
+use std::fmt;
+
+use rand::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::field::{replace_wiped, Field, Wipe};
+
+// Polynomial over a `Field`, coefficients lowest degree first and never
+// with trailing zeros, so the zero polynomial has no coefficients. The
+// constant term is usually a secret, so coefficients are wiped on drop
+// and Debug only prints the degree.
+#[derive(Clone)]
+pub struct Polynomial<F: Field> {
+    field: F,
+    coefficients: Vec<F::Elem>,
+}
+
+impl<F: Field> Polynomial<F> {
+    pub fn new(field: F, coefficients: Vec<F::Elem>) -> Self {
+        let mut polynomial = Self {
+            field,
+            coefficients,
+        };
+        polynomial.trim();
+        polynomial
+    }
+
+    pub fn zero(field: F) -> Self {
+        Self::new(field, vec![])
+    }
+
+    // Uniformly random of degree at most `degree`, with `constant` as
+    // the constant term: a sharing polynomial for the secret `constant`
+    pub fn random<R: RngCore + CryptoRng + ?Sized>(
+        field: F,
+        constant: F::Elem,
+        degree: usize,
+        rng: &mut R,
+    ) -> Self {
+        let mut coefficients = Vec::with_capacity(degree + 1);
+        coefficients.push(constant);
+        for _ in 0..degree {
+            coefficients.push(field.random(rng));
+        }
+        Self::new(field, coefficients)
+    }
+
+    // prod (x - x_i), zero at exactly the given points
+    pub fn vanishing(field: F, x_values: &[F::Elem]) -> Self {
+        let one = Self::new(field.clone(), vec![field.one()]);
+        x_values.iter().fold(one, |acc, x| {
+            let factor = Self::new(
+                field.clone(),
+                vec![field.sub(&field.zero(), x), field.one()],
+            );
+            acc.mul(&factor)
+        })
+    }
+
+    // The unique polynomial of degree < n through n points with distinct
+    // x values, as sum y_i * (V / (x - x_i)) / V'(x_i) where V is the
+    // vanishing polynomial of the x values
+    pub fn interpolate(field: F, x_values: &[F::Elem], y_values: &[F::Elem]) -> Result<Self> {
+        if x_values.len() != y_values.len() {
+            return Err(Error::IndexCountMismatch {
+                expected: y_values.len(),
+                got: x_values.len(),
+            });
+        }
+        let vanishing = Self::vanishing(field.clone(), x_values);
+        let n = x_values.len();
+
+        let mut result = vec![field.zero(); n];
+        for (x_i, y_i) in x_values.iter().zip(y_values) {
+            // Synthetic division of V by (x - x_i)
+            let mut basis = vec![field.zero(); n];
+            let mut carry = field.zero();
+            for k in (0..n).rev() {
+                carry = field.add(&vanishing.coefficients[k + 1], &field.mul(&carry, x_i));
+                basis[k] = carry.clone();
+            }
+            let basis = Self::new(field.clone(), basis);
+
+            let Some(inverse) = field.inverse(&basis.evaluate(x_i)) else {
+                result.wipe();
+                return Err(Error::DuplicateIndex);
+            };
+            let scale = field.mul(y_i, &inverse);
+            for (r, b) in result.iter_mut().zip(&basis.coefficients) {
+                let mut term = field.mul(&scale, b);
+                let sum = field.add(r, &term);
+                replace_wiped(r, sum);
+                term.wipe();
+            }
+        }
+        Ok(Self::new(field, result))
+    }
+
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    pub fn coefficients(&self) -> &[F::Elem] {
+        &self.coefficients
+    }
+
+    // Coefficient of x^i, zero past the degree
+    pub fn coefficient(&self, i: usize) -> F::Elem {
+        self.coefficients
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| self.field.zero())
+    }
+
+    pub fn constant_term(&self) -> F::Elem {
+        self.coefficient(0)
+    }
+
+    // None for the zero polynomial
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    // Horner's rule, wiping every intermediate value
+    pub fn evaluate(&self, x: &F::Elem) -> F::Elem {
+        let mut acc = self.field.zero();
+        for coeff in self.coefficients.iter().rev() {
+            let mut product = self.field.mul(&acc, x);
+            replace_wiped(&mut acc, self.field.add(&product, coeff));
+            product.wipe();
+        }
+        acc
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| self.field.add(a, b))
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| self.field.sub(a, b))
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self::zero(self.field.clone());
+        }
+        let mut result =
+            vec![self.field.zero(); self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, a) in self.coefficients.iter().enumerate() {
+            for (j, b) in other.coefficients.iter().enumerate() {
+                let mut product = self.field.mul(a, b);
+                let sum = self.field.add(&result[i + j], &product);
+                replace_wiped(&mut result[i + j], sum);
+                product.wipe();
+            }
+        }
+        Self::new(self.field.clone(), result)
+    }
+
+    pub fn scale(&self, factor: &F::Elem) -> Self {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .map(|c| self.field.mul(c, factor))
+            .collect();
+        Self::new(self.field.clone(), coefficients)
+    }
+
+    // Quotient and remainder, None when dividing by zero
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        let lead = self.field.inverse(divisor.coefficients.last()?)?;
+        let field = &self.field;
+        if self.coefficients.len() < divisor.coefficients.len() {
+            return Some((Self::zero(field.clone()), self.clone()));
+        }
+
+        let d = divisor.coefficients.len();
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![field.zero(); remainder.len() - d + 1];
+        for shift in (0..quotient.len()).rev() {
+            let factor = field.mul(&remainder[shift + d - 1], &lead);
+            for (i, c) in divisor.coefficients.iter().enumerate() {
+                let product = field.mul(&factor, c);
+                let difference = field.sub(&remainder[shift + i], &product);
+                replace_wiped(&mut remainder[shift + i], difference);
+            }
+            quotient[shift] = factor;
+        }
+        remainder[d - 1..].iter_mut().for_each(Wipe::wipe);
+        remainder.truncate(d - 1);
+        Some((
+            Self::new(field.clone(), quotient),
+            Self::new(field.clone(), remainder),
+        ))
+    }
+
+    fn zip_with(&self, other: &Self, op: impl Fn(&F::Elem, &F::Elem) -> F::Elem) -> Self {
+        let length = self.coefficients.len().max(other.coefficients.len());
+        let coefficients = (0..length)
+            .map(|i| op(&self.coefficient(i), &other.coefficient(i)))
+            .collect();
+        Self::new(self.field.clone(), coefficients)
+    }
+
+    fn trim(&mut self) {
+        while self
+            .coefficients
+            .last()
+            .is_some_and(|c| self.field.is_zero(c))
+        {
+            self.coefficients.pop();
+        }
+    }
+}
+
+// L_i(at) = prod_{j != i} (at - x_j) / (x_i - x_j) for every i: the
+// weights that interpolate values at `x_values` to the value at `at`.
+// They only depend on the x values, so they are public.
+pub fn lagrange_coefficients<F: Field>(
+    field: &F,
+    x_values: &[F::Elem],
+    at: &F::Elem,
+) -> Result<Vec<F::Elem>> {
+    x_values
+        .iter()
+        .enumerate()
+        .map(|(i, x_i)| {
+            let mut numerator = field.one();
+            let mut denominator = field.one();
+            for (j, x_j) in x_values.iter().enumerate() {
+                if i != j {
+                    numerator = field.mul(&numerator, &field.sub(at, x_j));
+                    denominator = field.mul(&denominator, &field.sub(x_i, x_j));
+                }
+            }
+            let inverse = field.inverse(&denominator).ok_or(Error::DuplicateIndex)?;
+            Ok(field.mul(&numerator, &inverse))
+        })
+        .collect()
+}
+
+impl<F: Field> PartialEq for Polynomial<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.field.id() == other.field.id() && self.coefficients == other.coefficients
+    }
+}
+
+impl<F: Field> fmt::Debug for Polynomial<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Polynomial")
+            .field("field", &self.field.id())
+            .field("degree", &self.degree())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field> Drop for Polynomial<F> {
+    fn drop(&mut self) {
+        self.coefficients.wipe();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Fp64, Gf256, Gf2_128, PrimeField};
+    use num_bigint::BigInt;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn interpolation_and_lagrange_weights_recover_random_polynomials() {
+        let field = Fp64::new(65537);
+        let at = field.elem_from_u64(100);
+        for degree in 0..6 {
+            let constant = field.random(&mut OsRng);
+            let p = Polynomial::random(field, constant, degree, &mut OsRng);
+            assert_eq!(p.constant_term(), constant);
+            assert!(p.degree().is_none_or(|d| d <= degree));
+
+            // degree + 1 points determine p
+            let x_values: Vec<_> = (1..=degree as u64 + 1)
+                .map(|x| field.elem_from_u64(x))
+                .collect();
+            let y_values: Vec<_> = x_values.iter().map(|x| p.evaluate(x)).collect();
+            assert_eq!(
+                Polynomial::interpolate(field, &x_values, &y_values).as_ref(),
+                Ok(&p)
+            );
+
+            let weights = lagrange_coefficients(&field, &x_values, &at).unwrap();
+            let mut value = field.zero();
+            for (w, y) in weights.iter().zip(&y_values) {
+                value = field.add(&value, &field.mul(w, y));
+            }
+            assert_eq!(value, p.evaluate(&at));
+        }
+    }
+
+    #[test]
+    fn division_undoes_multiplication() {
+        let field = PrimeField::new((BigInt::from(1) << 127) - 1);
+        let at = field.elem_from_u64(7);
+        let p = Polynomial::random(field.clone(), field.one(), 5, &mut OsRng);
+        // x^3 + 2x + 1 and 3x + 4, fixed so their degrees are known
+        let q = Polynomial::new(
+            field.clone(),
+            [1, 2, 0, 1].map(|c| field.elem_from_u64(c)).to_vec(),
+        );
+        let r = Polynomial::new(
+            field.clone(),
+            vec![field.elem_from_u64(4), field.elem_from_u64(3)],
+        );
+
+        let (quotient, remainder) = p.mul(&q).add(&r).div_rem(&q).unwrap();
+        assert_eq!((quotient, remainder), (p.clone(), r));
+        assert!(p.sub(&p).is_zero());
+        assert_eq!(
+            p.mul(&q).evaluate(&at),
+            field.mul(&p.evaluate(&at), &q.evaluate(&at))
+        );
+    }
+
+    #[test]
+    fn binary_fields_interpolate_and_divide() {
+        // In characteristic 2 addition and subtraction coincide, so
+        // (x + 2)(x + 3) = x^2 + x + 6 in GF(2^8)
+        let factors = [2, 3].map(|root| Polynomial::new(Gf256, vec![root, 1]));
+        let product = factors[0].mul(&factors[1]);
+        assert_eq!(product, Polynomial::new(Gf256, vec![6, 1, 1]));
+        assert_eq!(product, Polynomial::vanishing(Gf256, &[2, 3]));
+        let (quotient, remainder) = product.div_rem(&factors[0]).unwrap();
+        assert_eq!(quotient, factors[1]);
+        assert!(remainder.is_zero());
+
+        let field = Gf2_128;
+        let p = Polynomial::random(field, field.random(&mut OsRng), 4, &mut OsRng);
+        let x_values: Vec<_> = (1..=5).map(|x| field.elem_from_u64(x)).collect();
+        let y_values: Vec<_> = x_values.iter().map(|x| p.evaluate(x)).collect();
+        assert_eq!(
+            Polynomial::interpolate(field, &x_values, &y_values).as_ref(),
+            Ok(&p)
+        );
+    }
+
+    #[test]
+    fn vanishing_and_edge_cases() {
+        let field = Fp64::new(65537);
+        let x_values: Vec<_> = [2, 3, 5].map(|x| field.elem_from_u64(x)).to_vec();
+        let vanishing = Polynomial::vanishing(field, &x_values);
+        assert_eq!(vanishing.degree(), Some(3));
+        assert!(x_values
+            .iter()
+            .all(|x| field.is_zero(&vanishing.evaluate(x))));
+
+        // Trailing zeros are trimmed and the zero polynomial has no degree
+        let zero = Polynomial::new(field, vec![field.zero(), field.zero()]);
+        assert!(zero.is_zero() && zero.degree().is_none());
+        assert_eq!(zero, Polynomial::zero(field));
+        assert!(vanishing.div_rem(&zero).is_none());
+
+        let duplicate = [x_values[0], x_values[0]];
+        assert_eq!(
+            Polynomial::interpolate(field, &duplicate, &duplicate).err(),
+            Some(Error::DuplicateIndex)
+        );
+        assert_eq!(
+            lagrange_coefficients(&field, &duplicate, &field.zero()).err(),
+            Some(Error::DuplicateIndex)
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::sync::OnceLock;
+
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use rand::prelude::*;
+
+// Random Miller-Rabin rounds on top of the base-2 round and the Lucas test
+const MILLER_RABIN_ROUNDS: usize = 20;
+
+// How far a random starting point is walked before drawing a new one
+const SEARCH_WINDOW: u64 = 1 << 16;
+
+// Odd primes below 2^14, used for trial division and sieving
+fn small_primes() -> &'static [u64] {
+    static PRIMES: OnceLock<Vec<u64>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        const LIMIT: usize = 1 << 14;
+        let mut composite = vec![false; LIMIT];
+        let mut primes = vec![];
+        for i in 3..LIMIT {
+            if i % 2 == 1 && !composite[i] {
+                primes.push(i as u64);
+                for j in (i * i..LIMIT).step_by(i) {
+                    composite[j] = true;
+                }
+            }
+        }
+        primes
+    })
+}
+
+// Baillie-PSW (Miller-Rabin base 2 plus a strong Lucas test) with extra
+// random Miller-Rabin rounds. No composite is known to pass Baillie-PSW.
+pub fn is_probable_prime<R: RngCore + CryptoRng + ?Sized>(n: &BigInt, rng: &mut R) -> bool {
+    if *n < BigInt::from(2) {
+        return false;
+    }
+    for &p in small_primes() {
+        if *n == BigInt::from(p) {
+            return true;
+        }
+        if (n % p).is_zero() {
+            return false;
+        }
+    }
+    if !n.bit(0) {
+        return *n == BigInt::from(2);
+    }
+
+    if !miller_rabin(n, &BigInt::from(2)) || !strong_lucas(n) {
+        return false;
+    }
+    let upper = n - 2u32;
+    (0..MILLER_RABIN_ROUNDS).all(|_| {
+        let base = rng.gen_bigint_range(&BigInt::from(2), &upper);
+        miller_rabin(n, &base)
+    })
+}
+
+// Strong probable prime test to `base` for odd n > 3
+pub fn miller_rabin(n: &BigInt, base: &BigInt) -> bool {
+    let n_minus_one = n - 1u32;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+
+    let mut x = base.modpow(&d, n);
+    if x.is_one() || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = x.modpow(&BigInt::from(2), n);
+        if x == n_minus_one {
+            return true;
+        }
+        if x.is_one() {
+            return false;
+        }
+    }
+    false
+}
+
+// Jacobi symbol (a/n) for odd positive n
+fn jacobi(a: &BigInt, n: &BigInt) -> i32 {
+    let mut a = ((a % n) + n) % n;
+    let mut n = n.clone();
+    let mut result = 1;
+
+    while !a.is_zero() {
+        let twos = a.trailing_zeros().unwrap_or(0);
+        a >>= twos;
+        let n_mod_8 = (&n % 8u32).to_u32().unwrap_or(0);
+        if twos % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+            result = -result;
+        }
+        std::mem::swap(&mut a, &mut n);
+        if (&a % 4u32) == BigInt::from(3) && (&n % 4u32) == BigInt::from(3) {
+            result = -result;
+        }
+        a %= &n;
+    }
+
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+// Strong Lucas probable prime test with Selfridge's parameters:
+// D is the first of 5, -7, 9, -11, ... with (D/n) = -1, P = 1 and
+// Q = (1 - D) / 4. Expects odd n that passed trial division.
+pub fn strong_lucas(n: &BigInt) -> bool {
+    // Perfect squares have no D with (D/n) = -1
+    let root = n.sqrt();
+    if &root * &root == *n {
+        return false;
+    }
+
+    let mut d = BigInt::from(5);
+    loop {
+        match jacobi(&d, n) {
+            -1 => break,
+            0 if d.abs() != *n => return false,
+            _ => {}
+        }
+        d = if d.is_positive() {
+            -(d + 2u32)
+        } else {
+            -(d - 2u32)
+        };
+    }
+    let p = BigInt::one();
+    let q = (BigInt::one() - &d) / 4;
+
+    let reduce = |value: BigInt| ((value % n) + n) % n;
+    // Division by 2 mod odd n
+    let halve = |value: BigInt| {
+        let value = if value.bit(0) { value + n } else { value };
+        value >> 1
+    };
+
+    // n + 1 = k * 2^s with k odd
+    let n_plus_one = n + 1u32;
+    let s = n_plus_one.trailing_zeros().unwrap_or(0);
+    let k = &n_plus_one >> s;
+
+    // Left-to-right binary ladder for U_k, V_k and Q^k
+    let d_mod = reduce(d.clone());
+    let q_mod = reduce(q);
+    let mut u = BigInt::one();
+    let mut v = p.clone();
+    let mut q_k = q_mod.clone();
+    for bit in (0..k.bits() - 1).rev() {
+        u = reduce(&u * &v);
+        v = reduce(&v * &v - 2 * &q_k);
+        q_k = reduce(&q_k * &q_k);
+        if k.bit(bit) {
+            let next_u = halve(&p * &u + &v);
+            let next_v = halve(&d_mod * &u + &p * &v);
+            u = reduce(next_u);
+            v = reduce(next_v);
+            q_k = reduce(&q_k * &q_mod);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = reduce(&v * &v - 2 * &q_k);
+        if v.is_zero() {
+            return true;
+        }
+        q_k = reduce(&q_k * &q_k);
+    }
+    false
+}
+
+// Random safe prime p = 2q + 1 with exactly `bits` bits, q prime too.
+// Candidates for q are walked from a random odd start and sieved so
+// neither q nor 2q + 1 has a small factor before any modpow is spent.
+pub fn generate_safe_prime<R: RngCore + CryptoRng + ?Sized>(bits: u64, rng: &mut R) -> BigInt {
+    assert!(bits >= 16, "Safe primes below 16 bits are not supported");
+    let primes = small_primes();
+
+    loop {
+        // q has bits - 1 bits with the top bit set, so p has exactly `bits`
+        let mut start: BigInt = rng.gen_biguint(bits - 1).into();
+        start |= BigInt::one() << (bits - 2);
+        start |= BigInt::one();
+
+        let residues: Vec<u64> = primes
+            .iter()
+            .map(|&r| (&start % r).to_u64().unwrap_or(0))
+            .collect();
+
+        for delta in (0..SEARCH_WINDOW).step_by(2) {
+            // Skip any q where q or 2q + 1 is divisible by a small prime
+            let sieved = primes.iter().zip(&residues).all(|(&r, &residue)| {
+                let q_mod = (residue + delta) % r;
+                q_mod != 0 && (2 * q_mod + 1) % r != 0
+            });
+            if !sieved {
+                continue;
+            }
+
+            let q = &start + delta;
+            if q.bits() != bits - 1 {
+                break;
+            }
+            let p = 2 * &q + 1u32;
+
+            // Cheap base-2 checks on both before the full tests
+            let two = BigInt::from(2);
+            if !two.modpow(&(&p - 1u32), &p).is_one() || !miller_rabin(&q, &two) {
+                continue;
+            }
+            if is_probable_prime(&q, rng) && is_probable_prime(&p, rng) {
+                return p;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::OsRng;
+
+    fn is_prime_by_division(n: u64) -> bool {
+        n >= 2
+            && (2..)
+                .take_while(|d| d * d <= n)
+                .all(|d| !n.is_multiple_of(d))
+    }
+
+    #[test]
+    fn agrees_with_trial_division() {
+        // The second range is past the end of the trial division table,
+        // so the probabilistic tests decide it
+        for n in (0..1_000u64).chain(16_000..18_000) {
+            assert_eq!(
+                is_probable_prime(&BigInt::from(n), &mut OsRng),
+                is_prime_by_division(n),
+                "{}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_carmichael_numbers() {
+        for n in [561u64, 1105, 1729, 2465, 2821, 6601, 8911] {
+            assert!(!is_probable_prime(&BigInt::from(n), &mut OsRng), "{}", n);
+        }
+        // 17257 * 34513 * 51769, every factor beyond trial division
+        let n = BigInt::from(30833142247729u64);
+        assert!(BigInt::from(2).modpow(&(&n - 1u32), &n).is_one());
+        assert!(!is_probable_prime(&n, &mut OsRng));
+    }
+
+    #[test]
+    fn lucas_and_miller_rabin_cover_each_other() {
+        // Strong Lucas pseudoprimes for Selfridge's parameters
+        for n in [5459u64, 5777, 10877, 16109, 18971, 22499, 24569, 25199] {
+            let n = BigInt::from(n);
+            assert!(strong_lucas(&n), "{}", n);
+            assert!(!miller_rabin(&n, &BigInt::from(2)), "{}", n);
+            assert!(!is_probable_prime(&n, &mut OsRng), "{}", n);
+        }
+        // Strong pseudoprimes to base 2
+        for n in [2047u64, 3277, 4033, 4681, 8321] {
+            let n = BigInt::from(n);
+            assert!(miller_rabin(&n, &BigInt::from(2)), "{}", n);
+            assert!(!strong_lucas(&n), "{}", n);
+            assert!(!is_probable_prime(&n, &mut OsRng), "{}", n);
+        }
+    }
+
+    #[test]
+    fn large_mersenne_numbers() {
+        let mersenne = |e: u32| (BigInt::one() << e) - 1u32;
+        for e in [61, 89, 107, 127, 521] {
+            assert!(is_probable_prime(&mersenne(e), &mut OsRng), "{}", e);
+        }
+        // 2^67 - 1 = 193707721 * 761838257287, 2^128 + 1 is composite too
+        assert!(!is_probable_prime(&mersenne(67), &mut OsRng));
+        assert!(!is_probable_prime(
+            &((BigInt::one() << 128) + 1u32),
+            &mut OsRng
+        ));
+    }
+
+    #[test]
+    fn generates_safe_primes() {
+        let p = generate_safe_prime(64, &mut OsRng);
+        assert_eq!(p.bits(), 64);
+        assert!(is_probable_prime(&p, &mut OsRng));
+        assert!(is_probable_prime(&((&p - 1u32) >> 1), &mut OsRng));
+    }
+}
@@ -0,0 +1,291 @@
+use crate::error::{Error, Result};
+use crate::field::{replace_wiped, Field, Wipe};
+use crate::share::Share;
+
+// Barycentric Lagrange interpolation for a fixed quorum. With
+// l(x) = prod (x - x_j) and weights w_i = 1 / prod_{j != i} (x_i - x_j),
+// the basis value L_i(x) is l(x) * w_i / (x - x_i). Everything that only
+// depends on the x-coordinates is computed once in O(t^2), after which
+// each secret held by the quorum is a dot product, O(t) per combine.
+//
+// Only the x-coordinates are cached, they are public. Unlike
+// `SharmirModel::reconstruct_secret` nothing is checked against
+// commitments or for consistency, so this is for quorums that are
+// already trusted or verified.
+#[derive(Debug, Clone)]
+pub struct Reconstructor<F: Field> {
+    field: F,
+    // `field.id()` builds a fresh string, so it is cached for the share checks
+    field_id: String,
+    x_values: Vec<F::Elem>,
+    weights: Vec<F::Elem>,
+    // L_i(0), the coefficients that combine values into the secret
+    at_zero: Vec<F::Elem>,
+}
+
+impl<F: Field> Reconstructor<F> {
+    // Expects distinct, non-zero x-coordinates inside the field
+    pub fn new(field: F, x_values: &[F::Elem]) -> Result<Self> {
+        if x_values.is_empty() {
+            return Err(Error::InsufficientShares { needed: 1, got: 0 });
+        }
+        for (i, x) in x_values.iter().enumerate() {
+            if field.is_zero(x) {
+                return Err(Error::ZeroIndex);
+            }
+            if !field.contains(x) {
+                return Err(Error::OutOfRange);
+            }
+            if x_values[..i].contains(x) {
+                return Err(Error::DuplicateIndex);
+            }
+        }
+
+        let weights = x_values
+            .iter()
+            .enumerate()
+            .map(|(i, x_i)| {
+                let product = x_values
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| j != i)
+                    .fold(field.one(), |acc, (_, x_j)| {
+                        field.mul(&acc, &field.sub(x_i, x_j))
+                    });
+                field.inverse(&product).ok_or(Error::DuplicateIndex)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // l(0) = prod (-x_j), and 1 / (0 - x_i) exists as x_i is non-zero
+        let zero = field.zero();
+        let l_zero = x_values
+            .iter()
+            .fold(field.one(), |acc, x| field.mul(&acc, &field.sub(&zero, x)));
+        let at_zero = x_values
+            .iter()
+            .zip(&weights)
+            .map(|(x, w)| {
+                let inverse = field
+                    .inverse(&field.sub(&zero, x))
+                    .ok_or(Error::ZeroIndex)?;
+                Ok(field.mul(&l_zero, &field.mul(w, &inverse)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            field_id: field.id(),
+            field,
+            x_values: x_values.to_vec(),
+            weights,
+            at_zero,
+        })
+    }
+
+    pub fn field(&self) -> &F {
+        &self.field
+    }
+
+    pub fn x_values(&self) -> &[F::Elem] {
+        &self.x_values
+    }
+
+    // w_i = 1 / prod_{j != i} (x_i - x_j)
+    pub fn weights(&self) -> &[F::Elem] {
+        &self.weights
+    }
+
+    // The secret from the values at the cached x-coordinates, in the
+    // same order. Partial sums are wiped.
+    pub fn combine(&self, y_values: &[F::Elem]) -> Result<F::Elem> {
+        self.check_values(y_values)?;
+        Ok(self.dot(&self.at_zero, y_values.iter()))
+    }
+
+    // `combine` for shares, which must sit at the cached x-coordinates
+    // in the same order. Makes the checks of `share::check_shares` in
+    // O(t): the cached x-coordinates are already distinct and non-zero,
+    // so matching them position by position covers the indices.
+    pub fn combine_shares(&self, shares: &[Share<F>]) -> Result<F::Elem> {
+        self.check_count(shares.len())?;
+        let first = &shares[0];
+        if first.field_id != self.field_id {
+            return Err(Error::MismatchedShares);
+        }
+        if shares.len() < first.threshold {
+            return Err(Error::InsufficientShares {
+                needed: first.threshold,
+                got: shares.len(),
+            });
+        }
+        for (share, x) in shares.iter().zip(&self.x_values) {
+            if share.index != *x || !share.is_compatible(first) {
+                return Err(Error::MismatchedShares);
+            }
+            if !self.field.contains(share.expose_secret()) {
+                return Err(Error::OutOfRange);
+            }
+        }
+
+        Ok(self.dot(&self.at_zero, shares.iter().map(Share::expose_secret)))
+    }
+
+    // Value of the interpolating polynomial at any `at`, as
+    // l(at) * sum w_i y_i / (at - x_i). Costs one inversion per point on
+    // top of the O(t) sum, `combine` is the cheaper way to get at x = 0.
+    pub fn evaluate_at(&self, y_values: &[F::Elem], at: &F::Elem) -> Result<F::Elem> {
+        self.check_values(y_values)?;
+        if !self.field.contains(at) {
+            return Err(Error::OutOfRange);
+        }
+        if let Some(position) = self.x_values.iter().position(|x| x == at) {
+            return Ok(y_values[position].clone());
+        }
+
+        let l_at = self.x_values.iter().fold(self.field.one(), |acc, x| {
+            self.field.mul(&acc, &self.field.sub(at, x))
+        });
+        let coefficients = self
+            .x_values
+            .iter()
+            .zip(&self.weights)
+            .map(|(x, w)| {
+                let inverse = self
+                    .field
+                    .inverse(&self.field.sub(at, x))
+                    .ok_or(Error::DuplicateIndex)?;
+                Ok(self.field.mul(&l_at, &self.field.mul(w, &inverse)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.dot(&coefficients, y_values.iter()))
+    }
+
+    fn check_count(&self, got: usize) -> Result<()> {
+        if got != self.x_values.len() {
+            return Err(Error::IndexCountMismatch {
+                expected: self.x_values.len(),
+                got,
+            });
+        }
+        Ok(())
+    }
+
+    // One value per cached x-coordinate, each inside the field
+    fn check_values(&self, y_values: &[F::Elem]) -> Result<()> {
+        self.check_count(y_values.len())?;
+        if !y_values.iter().all(|y| self.field.contains(y)) {
+            return Err(Error::OutOfRange);
+        }
+        Ok(())
+    }
+
+    // sum c_i y_i, wiping every intermediate value
+    fn dot<'a>(
+        &self,
+        coefficients: &[F::Elem],
+        y_values: impl Iterator<Item = &'a F::Elem>,
+    ) -> F::Elem
+    where
+        F::Elem: 'a,
+    {
+        let mut result = self.field.zero();
+        for (coefficient, y) in coefficients.iter().zip(y_values) {
+            let mut term = self.field.mul(y, coefficient);
+            let sum = self.field.add(&result, &term);
+            replace_wiped(&mut result, sum);
+            term.wipe();
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Fp64, PrimeField};
+    use crate::shamir::SharmirModel;
+    use num_bigint::BigInt;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn combines_many_secrets_of_one_quorum() {
+        let field = Fp64::new(65537);
+        let x_values: Vec<_> = [3, 7, 11].map(|x| field.elem_from_u64(x)).to_vec();
+        let reconstructor = Reconstructor::new(field, &x_values).unwrap();
+
+        for secret in 0..20 {
+            let secret = field.elem_from_u64(secret * 1000);
+            let mut model = SharmirModel::with_field(field, secret, 3, 3).unwrap();
+            model.generate_shares_at(&x_values, &mut OsRng).unwrap();
+            let shares = model.get_shares().clone();
+            assert_eq!(reconstructor.combine_shares(&shares), Ok(secret));
+
+            let y_values: Vec<_> = shares.iter().map(|s| *s.expose_secret()).collect();
+            assert_eq!(reconstructor.combine(&y_values), Ok(secret));
+            assert_eq!(
+                reconstructor.evaluate_at(&y_values, &field.zero()),
+                Ok(secret)
+            );
+            let at = field.elem_from_u64(5);
+            assert_eq!(
+                reconstructor.evaluate_at(&y_values, &at),
+                model.interpolate_at(&shares, &at)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_shares_off_the_quorum() {
+        let field = Fp64::new(65537);
+        let x_values: Vec<_> = [1, 2, 3].map(|x| field.elem_from_u64(x)).to_vec();
+        let reconstructor = Reconstructor::new(field, &x_values).unwrap();
+
+        let mut model = SharmirModel::with_field(field, field.one(), 3, 3).unwrap();
+        model.generate_shares(&mut OsRng);
+        let mut shares = model.get_shares().clone();
+        shares.swap(0, 1);
+        assert_eq!(
+            reconstructor.combine_shares(&shares),
+            Err(Error::MismatchedShares)
+        );
+
+        let mut other = SharmirModel::with_field(field, field.one(), 3, 3).unwrap();
+        other.generate_shares(&mut OsRng);
+        shares.swap(0, 1);
+        shares[2] = other.get_shares()[2].clone();
+        assert_eq!(
+            reconstructor.combine_shares(&shares),
+            Err(Error::MismatchedShares)
+        );
+
+        assert_eq!(
+            Reconstructor::new(field, &[x_values[0], x_values[0]]).err(),
+            Some(Error::DuplicateIndex)
+        );
+        assert_eq!(
+            Reconstructor::new(field, &[field.zero()]).err(),
+            Some(Error::ZeroIndex)
+        );
+    }
+
+    #[test]
+    fn rejects_values_outside_the_field() {
+        let field = PrimeField::new(BigInt::from(65537));
+        let x_values = [1, 2].map(BigInt::from);
+        let reconstructor = Reconstructor::new(field, &x_values).unwrap();
+        assert_eq!(
+            reconstructor.combine(&[BigInt::from(5), BigInt::from(7)]),
+            Ok(BigInt::from(3))
+        );
+
+        let y_values = [BigInt::from(5), BigInt::from(65537 + 7)];
+        assert_eq!(reconstructor.combine(&y_values), Err(Error::OutOfRange));
+        assert_eq!(
+            reconstructor.evaluate_at(&y_values, &BigInt::from(3)),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(
+            reconstructor.combine(&[BigInt::from(-1), BigInt::from(7)]),
+            Err(Error::OutOfRange)
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use crate::error::{Error, Result};
+use crate::field::{Field, Wipe};
+use crate::polynomial::Polynomial;
+
+// Error-correcting reconstruction. n shares of a threshold-t sharing
+// are a Reed-Solomon codeword, the evaluations of a degree < t
+// polynomial at n distinct points, so Gao's decoder recovers the
+// polynomial while at most (n - t) / 2 shares are wrong. Needs no
+// commitments, only redundancy.
+
+// Give up on the exhaustive search for a consistent subset beyond this
+// many candidate polynomials
+const MAX_SUBSETS: usize = 1 << 16;
+
+// Which shares of a set agree with the polynomial recovered from it,
+// named by their x-coordinates, the share indices
+#[derive(Debug, Clone)]
+pub struct ConsistencyReport<F: Field> {
+    pub consistent: Vec<F::Elem>,
+    pub inconsistent: Vec<F::Elem>,
+}
+
+impl<F: Field> ConsistencyReport<F> {
+    pub fn is_clean(&self) -> bool {
+        self.inconsistent.is_empty()
+    }
+}
+
+// How many wrong shares `decode` corrects
+pub fn max_faults(shares: usize, threshold: usize) -> usize {
+    shares.saturating_sub(threshold) / 2
+}
+
+// Returns the sharing polynomial and the x values of the points that
+// are off it. Expects distinct x values.
+pub fn decode<F: Field>(
+    field: &F,
+    x_values: &[F::Elem],
+    y_values: &[F::Elem],
+    threshold: usize,
+) -> Result<(Polynomial<F>, Vec<F::Elem>)> {
+    let n = x_values.len();
+    if y_values.len() != n {
+        return Err(Error::IndexCountMismatch {
+            expected: y_values.len(),
+            got: n,
+        });
+    }
+    if threshold == 0 || n < threshold {
+        return Err(Error::InsufficientShares {
+            needed: threshold.max(1),
+            got: n,
+        });
+    }
+    let tolerated = max_faults(n, threshold);
+
+    // g0 = prod (x - x_i), g1 interpolates every point
+    let g0 = Polynomial::vanishing(field.clone(), x_values);
+    let g1 = Polynomial::interpolate(field.clone(), x_values, y_values)?;
+
+    // Partial extended Euclid on (g0, g1), stopping at the first
+    // remainder of degree < (n + t) / 2 and keeping its g1 cofactor v.
+    // Every polynomial wipes itself when replaced.
+    let (mut r_prev, mut r) = (g0, g1);
+    let (mut v_prev, mut v) = (
+        Polynomial::zero(field.clone()),
+        Polynomial::new(field.clone(), vec![field.one()]),
+    );
+    while r.degree().is_some_and(|d| 2 * d >= n + threshold) {
+        let (quotient, remainder) = r_prev
+            .div_rem(&r)
+            .ok_or(Error::TooManyFaults { tolerated })?;
+        let v_next = v_prev.sub(&quotient.mul(&v));
+        r_prev = std::mem::replace(&mut r, remainder);
+        v_prev = std::mem::replace(&mut v, v_next);
+    }
+
+    // The error locator v divides r exactly when few enough points are wrong
+    let (polynomial, remainder) = r.div_rem(&v).ok_or(Error::TooManyFaults { tolerated })?;
+    if !remainder.is_zero() || polynomial.degree().is_some_and(|d| d >= threshold) {
+        return Err(Error::TooManyFaults { tolerated });
+    }
+
+    let faulty = report(&polynomial, x_values, y_values).inconsistent;
+    if faulty.len() > tolerated {
+        return Err(Error::TooManyFaults { tolerated });
+    }
+    Ok((polynomial, faulty))
+}
+
+// All points lie on one polynomial of degree < threshold
+pub fn is_consistent<F: Field>(
+    field: &F,
+    x_values: &[F::Elem],
+    y_values: &[F::Elem],
+    threshold: usize,
+) -> Result<bool> {
+    let polynomial = Polynomial::interpolate(field.clone(), x_values, y_values)?;
+    Ok(polynomial.degree().is_none_or(|d| d < threshold))
+}
+
+// The polynomial of degree < threshold agreeing with the most points,
+// and which points agree with it. Decoding finds it directly while the
+// disagreeing points are at most (n - t) / 2. Past that, every t-subset
+// is interpolated and the best candidate must beat all others and agree
+// with more than t points, otherwise nothing singles it out. With more
+// than `MAX_SUBSETS` subsets the search isn't attempted.
+pub fn largest_consistent<F: Field>(
+    field: &F,
+    x_values: &[F::Elem],
+    y_values: &[F::Elem],
+    threshold: usize,
+) -> Result<(Polynomial<F>, ConsistencyReport<F>)> {
+    match decode(field, x_values, y_values, threshold) {
+        Ok((poly, _)) => {
+            let report = report(&poly, x_values, y_values);
+            return Ok((poly, report));
+        }
+        Err(Error::TooManyFaults { .. }) => {}
+        Err(err) => return Err(err),
+    }
+
+    let n = x_values.len();
+    if binomial(n, threshold) > MAX_SUBSETS {
+        return Err(Error::TooManySubsets { max: MAX_SUBSETS });
+    }
+
+    let mut best: Option<(Polynomial<F>, usize)> = None;
+    let mut tied = false;
+    let mut subset: Vec<usize> = (0..threshold).collect();
+    loop {
+        let xs: Vec<F::Elem> = subset.iter().map(|&i| x_values[i].clone()).collect();
+        let mut ys: Vec<F::Elem> = subset.iter().map(|&i| y_values[i].clone()).collect();
+        let candidate = Polynomial::interpolate(field.clone(), &xs, &ys);
+        ys.wipe();
+        let candidate = candidate?;
+        let agreeing = agreeing(&candidate, x_values, y_values);
+
+        match &mut best {
+            Some((poly, count)) if agreeing > *count => {
+                *poly = candidate;
+                *count = agreeing;
+                tied = false;
+            }
+            Some((poly, count)) => {
+                if agreeing == *count && *poly != candidate {
+                    tied = true;
+                }
+            }
+            None => best = Some((candidate, agreeing)),
+        }
+
+        if !next_subset(&mut subset, n) {
+            break;
+        }
+    }
+
+    match best {
+        Some((poly, count)) if count > threshold && !tied => {
+            let report = report(&poly, x_values, y_values);
+            Ok((poly, report))
+        }
+        _ => Err(Error::InconsistentShares),
+    }
+}
+
+// Splits the x values by whether `poly` passes through their point
+fn report<F: Field>(
+    poly: &Polynomial<F>,
+    x_values: &[F::Elem],
+    y_values: &[F::Elem],
+) -> ConsistencyReport<F> {
+    let mut report = ConsistencyReport {
+        consistent: vec![],
+        inconsistent: vec![],
+    };
+    for (x, y) in x_values.iter().zip(y_values) {
+        if poly.evaluate(x) == *y {
+            report.consistent.push(x.clone());
+        } else {
+            report.inconsistent.push(x.clone());
+        }
+    }
+    report
+}
+
+// Number of points `poly` passes through
+fn agreeing<F: Field>(poly: &Polynomial<F>, x_values: &[F::Elem], y_values: &[F::Elem]) -> usize {
+    x_values
+        .iter()
+        .zip(y_values)
+        .filter(|(x, y)| poly.evaluate(x) == **y)
+        .count()
+}
+
+// Advances `subset` to the next k-combination of 0..n in lexicographic
+// order, false after the last one
+fn next_subset(subset: &mut [usize], n: usize) -> bool {
+    let k = subset.len();
+    for i in (0..k).rev() {
+        if subset[i] < n - k + i {
+            subset[i] += 1;
+            for j in i + 1..k {
+                subset[j] = subset[j - 1] + 1;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+// n choose k for k <= n, usize::MAX on overflow
+fn binomial(n: usize, k: usize) -> usize {
+    let k = k.min(n - k);
+    (0..k)
+        .try_fold(1usize, |acc, i| acc.checked_mul(n - i).map(|c| c / (i + 1)))
+        .unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Fp64, Gf256, Gf2_128};
+
+    // Evaluations of 1 + 2x + ... + t x^(t-1) at x = 1..=n, with the
+    // points at `corrupt` moved off the polynomial
+    fn codeword<F: Field>(
+        field: &F,
+        n: usize,
+        threshold: usize,
+        corrupt: &[usize],
+    ) -> (Polynomial<F>, Vec<F::Elem>, Vec<F::Elem>) {
+        let coefficients = (1..=threshold as u64)
+            .map(|c| field.elem_from_u64(c))
+            .collect();
+        let polynomial = Polynomial::new(field.clone(), coefficients);
+        let x_values: Vec<F::Elem> = (1..=n as u64).map(|x| field.elem_from_u64(x)).collect();
+        let y_values = x_values
+            .iter()
+            .enumerate()
+            .map(|(position, x)| {
+                let y = polynomial.evaluate(x);
+                if corrupt.contains(&position) {
+                    field.add(&y, &field.elem_from_u64(position as u64 + 1))
+                } else {
+                    y
+                }
+            })
+            .collect();
+        (polynomial, x_values, y_values)
+    }
+
+    #[test]
+    fn decodes_up_to_half_the_redundancy() {
+        let field = Fp64::default_prime();
+        for (n, threshold) in [(4, 3), (5, 3), (7, 3), (10, 4), (12, 2)] {
+            let tolerated = max_faults(n, threshold);
+            let corrupt: Vec<usize> = (0..n).step_by(2).take(tolerated).collect();
+
+            let (polynomial, x_values, y_values) = codeword(&field, n, threshold, &corrupt);
+            let (decoded, faulty) = decode(&field, &x_values, &y_values, threshold).unwrap();
+            assert_eq!(decoded, polynomial);
+            // Faulty shares are named by index, x = position + 1 here
+            let indices: Vec<u64> = faulty.iter().map(|x| field.canonical(x)).collect();
+            let expected: Vec<u64> = corrupt.iter().map(|&p| p as u64 + 1).collect();
+            assert_eq!(indices, expected);
+
+            // One more is past unique decoding but still detected
+            let mut corrupt = corrupt;
+            corrupt.push(n - 1);
+            let (_, x_values, y_values) = codeword(&field, n, threshold, &corrupt);
+            assert_eq!(
+                decode(&field, &x_values, &y_values, threshold).err(),
+                Some(Error::TooManyFaults { tolerated })
+            );
+        }
+
+        // With n = t every set of values lies on some polynomial
+        let (polynomial, x_values, y_values) = codeword(&field, 3, 3, &[]);
+        assert_eq!(
+            decode(&field, &x_values, &y_values, 3),
+            Ok((polynomial, vec![]))
+        );
+    }
+
+    #[test]
+    fn decodes_in_binary_fields() {
+        let (polynomial, x_values, y_values) = codeword(&Gf256, 9, 3, &[1, 5, 8]);
+        let (decoded, faulty) = decode(&Gf256, &x_values, &y_values, 3).unwrap();
+        assert_eq!((decoded, faulty), (polynomial, vec![2, 6, 9]));
+
+        let (polynomial, x_values, y_values) = codeword(&Gf2_128, 6, 4, &[0]);
+        let (decoded, faulty) = decode(&Gf2_128, &x_values, &y_values, 4).unwrap();
+        assert_eq!((decoded, faulty), (polynomial, vec![1]));
+    }
+
+    #[test]
+    fn consistency() {
+        let field = Fp64::default_prime();
+        let (_, x_values, y_values) = codeword(&field, 6, 3, &[]);
+        assert_eq!(is_consistent(&field, &x_values, &y_values, 3), Ok(true));
+        let (_, x_values, y_values) = codeword(&field, 6, 3, &[4]);
+        assert_eq!(is_consistent(&field, &x_values, &y_values, 3), Ok(false));
+    }
+
+    #[test]
+    fn largest_consistent_beyond_decoding() {
+        let field = Gf256;
+        // 3 of 8 wrong with threshold 3: decoding corrects 2, the
+        // remaining 5 still single out the polynomial
+        let (polynomial, x_values, y_values) = codeword(&field, 8, 3, &[0, 3, 6]);
+        let (found, report) = largest_consistent(&field, &x_values, &y_values, 3).unwrap();
+        assert_eq!(found, polynomial);
+        assert_eq!(report.inconsistent, vec![1, 4, 7]);
+        assert_eq!(report.consistent, vec![2, 3, 5, 6, 8]);
+        assert!(!report.is_clean());
+
+        // 3 right and 3 wrong leaves nothing to tell them apart
+        let (_, x_values, y_values) = codeword(&field, 6, 3, &[0, 1, 2]);
+        assert_eq!(
+            largest_consistent(&field, &x_values, &y_values, 3).err(),
+            Some(Error::InconsistentShares)
+        );
+
+        // C(40, 10) subsets are far too many to try
+        let corrupt: Vec<usize> = (0..20).collect();
+        let (_, x_values, y_values) = codeword(&field, 40, 10, &corrupt);
+        assert_eq!(
+            largest_consistent(&field, &x_values, &y_values, 10).err(),
+            Some(Error::TooManySubsets { max: MAX_SUBSETS })
+        );
+    }
+}
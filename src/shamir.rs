@@ -1,123 +1,1251 @@
+use std::fmt;
 use std::vec;
 
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use rand::prelude::*;
+use rand::rngs::OsRng;
 
-use crate::vss::{VSSCommitments, VSSParams};
+use crate::error::{Error, Result};
+use crate::field::{replace_wiped, Field, PrimeField, Wipe};
+use crate::pedersen::PedersenCommitments;
+use crate::polynomial::{self, Polynomial};
+use crate::robust::{self, ConsistencyReport};
+use crate::share::{self, SetId, Share};
+use crate::vss::{Group, VSSCommitments, VSSParams};
 
-#[derive(Debug, Clone)]
-pub struct SharmirModel {
-    secret: i64,
+// Not Clone on purpose: copies of the secret state have to be asked
+// for through `duplicate`. Secret, polynomials and shares are wiped
+// on drop, and Debug only prints metadata. `G` is the group the
+// Feldman or Pedersen commitments live in, if VSS is enabled.
+pub struct SharmirModel<F: Field, G: Group = VSSParams> {
+    field: F,
+    secret: F::Elem,
     shares: usize,
     threshold: usize,
-    generated_shares: Vec<(i64, i64)>,
-    coefficients: Vec<i64>,
-    vss_commitments: Option<VSSCommitments>,
-    vss_params: VSSParams,
+    set_id: SetId,
+    generated_shares: Vec<Share<F>>,
+    polynomial: Option<Polynomial<F>>,
+    blinding: Option<Polynomial<F>>,
+    vss_commitments: Option<VSSCommitments<G>>,
+    pedersen_commitments: Option<PedersenCommitments<G>>,
+    vss_params: Option<G>,
+    hiding: bool,
 }
 
-impl SharmirModel {
-    pub fn new(secret: i64, shares: usize, threshold: usize) -> Self {
-        Self {
+impl SharmirModel<PrimeField> {
+    pub fn new(secret: BigInt, shares: usize, threshold: usize) -> Result<Self> {
+        let field = PrimeField::for_secret(&secret)?;
+        Self::with_field(field, secret, shares, threshold)
+    }
+
+    // Big-endian bytes, e.g. an AES key or an Ed25519 scalar
+    pub fn from_bytes(secret: &[u8], shares: usize, threshold: usize) -> Result<Self> {
+        Self::new(BigInt::from_bytes_be(Sign::Plus, secret), shares, threshold)
+    }
+
+    // `modulus` must be a prime larger than the secret, it is tested for
+    // primality by `PrimeField::try_new`
+    pub fn with_modulus(
+        secret: BigInt,
+        modulus: BigInt,
+        shares: usize,
+        threshold: usize,
+    ) -> Result<Self> {
+        let field = PrimeField::try_new(modulus)?;
+        Self::with_field(field, secret, shares, threshold)
+    }
+}
+
+impl<G: Group> SharmirModel<PrimeField, G> {
+    // Shares the secret in the scalar field of `group` (Z_q for a mod-p
+    // group, Z_l or Z_n for a curve) and publishes Feldman commitments,
+    // the secret has to be below the group order
+    pub fn with_vss(secret: BigInt, shares: usize, threshold: usize, group: G) -> Result<Self> {
+        group.validate()?;
+        let field = PrimeField::new(group.order());
+        let mut model = Self::build(field, secret, shares, threshold)?;
+        model.enable_vss(group)?;
+        Ok(model)
+    }
+
+    // Same as `with_vss` but with Pedersen commitments, which hide the
+    // secret unconditionally. Every share carries a blinding value.
+    pub fn with_pedersen(
+        secret: BigInt,
+        shares: usize,
+        threshold: usize,
+        group: G,
+    ) -> Result<Self> {
+        group.validate()?;
+        let field = PrimeField::new(group.order());
+        let mut model = Self::build(field, secret, shares, threshold)?;
+        model.enable_pedersen(group)?;
+        Ok(model)
+    }
+
+    // A combiner's model of a sharing someone else dealt: the Feldman
+    // commitments the dealer published and the set id on the shares.
+    // There is no secret, shares can be verified and combined, with the
+    // result checked against the commitments, but not generated.
+    pub fn from_vss_commitments(
+        commitments: VSSCommitments<G>,
+        set_id: SetId,
+        group: G,
+    ) -> Result<Self> {
+        let mut model = Self::combiner(commitments.points().len(), set_id, &group)?;
+        model.vss_commitments = Some(commitments);
+        model.vss_params = Some(group);
+        Ok(model)
+    }
+
+    // Same as `from_vss_commitments` for a Pedersen sharing
+    pub fn from_pedersen_commitments(
+        commitments: PedersenCommitments<G>,
+        set_id: SetId,
+        group: G,
+    ) -> Result<Self> {
+        let mut model = Self::combiner(commitments.points().len(), set_id, &group)?;
+        model.pedersen_commitments = Some(commitments);
+        model.vss_params = Some(group);
+        model.hiding = true;
+        Ok(model)
+    }
+
+    // One commitment per coefficient, so their number is the threshold
+    fn combiner(threshold: usize, set_id: SetId, group: &G) -> Result<Self> {
+        group.validate()?;
+        let field = PrimeField::new(group.order());
+        let mut model = Self::build(field.clone(), field.zero(), threshold, threshold)?;
+        model.set_id = set_id;
+        Ok(model)
+    }
+}
+
+impl<F: Field> SharmirModel<F> {
+    pub fn with_field(field: F, secret: F::Elem, shares: usize, threshold: usize) -> Result<Self> {
+        Self::build(field, secret, shares, threshold)
+    }
+}
+
+impl<F: Field, G: Group> SharmirModel<F, G> {
+    fn build(field: F, secret: F::Elem, shares: usize, threshold: usize) -> Result<Self> {
+        if threshold == 0 || threshold > shares {
+            return Err(Error::InvalidThreshold { threshold, shares });
+        }
+        // Every share needs its own non-zero x-coordinate
+        let max = field.order() - 1u32;
+        if BigInt::from(shares) > max {
+            return Err(Error::TooManyShares {
+                shares,
+                max: usize::try_from(max).unwrap_or(usize::MAX),
+            });
+        }
+        if !field.contains(&secret) {
+            return Err(Error::OutOfRange);
+        }
+
+        Ok(Self {
+            field,
             secret,
             shares,
             threshold,
+            set_id: SetId::default(),
             generated_shares: vec![],
-            coefficients: vec![],
+            polynomial: None,
+            blinding: None,
             vss_commitments: None,
-            vss_params: VSSParams::new(),
-        }
+            pedersen_commitments: None,
+            vss_params: None,
+            hiding: false,
+        })
     }
 
-    pub fn construct_polynomial(&mut self, x: i64) -> i64 {
-        let mut rng = rand::thread_rng();
-        let mut sum = self.secret;
+    pub fn field(&self) -> &F {
+        &self.field
+    }
 
-        // Store coefficients for VSS if not already generated
-        if self.coefficients.is_empty() {
-            self.coefficients = vec![self.secret];
-            for _ in 1..self.threshold {
-                let coefficient = rng.gen_range(1..=self.secret / 2);
-                self.coefficients.push(coefficient);
-            }
-            // Generate VSS commitments
-            self.vss_commitments = Some(VSSCommitments::new(&self.coefficients, &self.vss_params));
+    pub fn set_id(&self) -> SetId {
+        self.set_id
+    }
+
+    // Feldman commitments only verify when the polynomial lives in the
+    // exponent group of the generator, so the sharing field has to be
+    // exactly Z_order. Like `enable_pedersen` this fails once the first
+    // share is generated, the commitment mode is fixed from then on.
+    pub fn enable_vss(&mut self, group: G) -> Result<()> {
+        self.set_group(group, false)
+    }
+
+    // Pedersen commitments instead of Feldman ones. Every share needs a
+    // blinding value, so this too has to come before the first share.
+    pub fn enable_pedersen(&mut self, group: G) -> Result<()> {
+        self.set_group(group, true)
+    }
+
+    pub fn vss_commitments(&self) -> Option<&VSSCommitments<G>> {
+        self.vss_commitments.as_ref()
+    }
+
+    pub fn pedersen_commitments(&self) -> Option<&PedersenCommitments<G>> {
+        self.pedersen_commitments.as_ref()
+    }
+
+    pub fn expose_secret(&self) -> &F::Elem {
+        &self.secret
+    }
+
+    // Explicit copy of the whole state, including the secret
+    pub fn duplicate(&self) -> Self {
+        Self {
+            field: self.field.clone(),
+            secret: self.secret.clone(),
+            shares: self.shares,
+            threshold: self.threshold,
+            set_id: self.set_id,
+            generated_shares: self.generated_shares.clone(),
+            polynomial: self.polynomial.clone(),
+            blinding: self.blinding.clone(),
+            vss_commitments: self.vss_commitments.clone(),
+            pedersen_commitments: self.pedersen_commitments.clone(),
+            vss_params: self.vss_params.clone(),
+            hiding: self.hiding,
         }
+    }
 
-        for (power, &coeff) in self.coefficients[1..].iter().enumerate() {
-            sum += coeff * x.pow((power + 1) as u32);
+    // Value of the sharing polynomial at `x`, sampling it on first use.
+    // Private since at x = 0 this is the secret, shares only come out of
+    // `generate_shares_at`, which rejects a zero index.
+    fn construct_polynomial<R: RngCore + CryptoRng + ?Sized>(
+        &mut self,
+        x: &F::Elem,
+        rng: &mut R,
+    ) -> F::Elem {
+        // Store coefficients for VSS if not already generated,
+        // a fresh polynomial also starts a fresh share set
+        let mut fresh = false;
+        if self.polynomial.is_none() {
+            self.set_id = SetId::random(rng);
+            self.polynomial = Some(Polynomial::random(
+                self.field.clone(),
+                self.secret.clone(),
+                self.threshold - 1,
+                rng,
+            ));
+            fresh = true;
+        }
+        // Pedersen's blinding polynomial is uniformly random, constant
+        // term included
+        if self.hiding && self.blinding.is_none() {
+            let constant = self.field.random(rng);
+            self.blinding = Some(Polynomial::random(
+                self.field.clone(),
+                constant,
+                self.threshold - 1,
+                rng,
+            ));
+            fresh = true;
+        }
+        if fresh {
+            self.commit();
         }
 
-        sum
+        self.polynomial
+            .as_ref()
+            .expect("Generated above")
+            .evaluate(x)
     }
 
-    pub fn verify_share(&self, x: i64, share: i64) -> bool {
-        if let Some(commitments) = &self.vss_commitments {
-            commitments.verify_share(x, share, &self.vss_params)
+    pub fn verify_share(&self, share: &Share<F>) -> Result<bool> {
+        self.check_share(share)?;
+        let Some(group) = &self.vss_params else {
+            return Err(Error::MissingCommitments);
+        };
+
+        let x = self.field.to_bigint(&share.index);
+        let mut value = self.field.to_bigint(share.expose_secret());
+        let is_valid = if let Some(commitments) = &self.pedersen_commitments {
+            match share.expose_blinding() {
+                Some(blinding) if self.field.contains(blinding) => {
+                    let mut blinding = self.field.to_bigint(blinding);
+                    let is_valid = commitments.verify_share(&x, &value, &blinding, group);
+                    blinding.wipe();
+                    is_valid
+                }
+                Some(_) => Err(Error::OutOfRange),
+                None => Err(Error::MissingBlinding),
+            }
+        } else if let Some(commitments) = &self.vss_commitments {
+            commitments.verify_share(&x, &value, group)
         } else {
-            false
+            Err(Error::MissingCommitments)
+        };
+        value.wipe();
+        is_valid
+    }
+
+    // Positions in `shares` of the shares that fail verification, empty
+    // if all pass. Feldman commitments check the whole set in one batch,
+    // Pedersen ones share by share.
+    pub fn verify_shares(&self, shares: &[Share<F>]) -> Result<Vec<usize>> {
+        for share in shares {
+            self.check_share(share)?;
         }
+        if let (Some(commitments), Some(group)) = (&self.vss_commitments, &self.vss_params) {
+            return commitments.verify_batch(&self.field, shares, group);
+        }
+
+        let mut invalid = vec![];
+        for (position, share) in shares.iter().enumerate() {
+            if !self.verify_share(share)? {
+                invalid.push(position);
+            }
+        }
+        Ok(invalid)
     }
 
     // Simply return a reference to generated_shares
     // Use &self as parameter to borrow immutably
-    pub fn get_shares(&mut self) -> &Vec<(i64, i64)> {
+    pub fn get_shares(&mut self) -> &Vec<Share<F>> {
         &self.generated_shares
     }
 
-    // 1. Create empty vector for shares
-    // 2. Loop from 0 to self.shares
-    // 3. For each iteration:
-    //    - Convert loop index to i64 for x value
+    // Default x-coordinates 1..=shares, x = 0 would hand out the secret itself.
+    // Production callers pass OsRng, tests can pass a seeded ChaCha RNG
+    // to get reproducible shares. Panics on a combiner's model.
+    pub fn generate_shares<R: RngCore + CryptoRng + ?Sized>(&mut self, rng: &mut R) {
+        let indices: Vec<F::Elem> = (1..=self.shares)
+            .map(|i| self.field.elem_from_u64(i as u64))
+            .collect();
+        self.generate_shares_at(&indices, rng)
+            .expect("Default indices are distinct and non-zero, and only dealers generate shares");
+    }
+
+    // 1. Check there is one non-zero, distinct x-coordinate per participant
+    // 2. Create empty vector for shares
+    // 3. For each x-coordinate:
     //    - Call construct_polynomial(x) to get y value
-    //    - Push tuple (x,y) to shares vector
+    //    - Push Share (x, y, metadata) to shares vector
     // 4. Finally assign shares vector to self.generated_shares
     // Note: Need &mut self since we're modifying state
-    pub fn generate_shares(&mut self) {
-        let mut new_shares: Vec<(i64, i64)> = vec![];
+    pub fn generate_shares_at<R: RngCore + CryptoRng + ?Sized>(
+        &mut self,
+        indices: &[F::Elem],
+        rng: &mut R,
+    ) -> Result<()> {
+        // Commitments without a polynomial were received, not dealt, and
+        // a fresh polynomial would replace them
+        if self.polynomial.is_none() && self.is_committed() {
+            return Err(Error::InvalidParams(
+                "a model built from commitments cannot generate shares",
+            ));
+        }
+        if indices.len() != self.shares {
+            return Err(Error::IndexCountMismatch {
+                expected: self.shares,
+                got: indices.len(),
+            });
+        }
+        for (i, x) in indices.iter().enumerate() {
+            if self.field.is_zero(x) {
+                return Err(Error::ZeroIndex);
+            }
+            if !self.field.contains(x) {
+                return Err(Error::OutOfRange);
+            }
+            if indices[..i].contains(x) {
+                return Err(Error::DuplicateIndex);
+            }
+        }
+
+        let mut new_shares: Vec<Share<F>> = vec![];
 
-        for i in 0..self.shares {
-            let x = i as i64;
-            let y = self.construct_polynomial(x);
-            new_shares.push((x, y));
+        for x in indices.iter().cloned() {
+            let y = self.construct_polynomial(&x, rng);
+            let mut share = Share::new(x, y, self.set_id, self.threshold, self.field.id());
+            if let (true, Some(blinding)) = (self.hiding, &self.blinding) {
+                let t = blinding.evaluate(&share.index);
+                share = share.with_blinding(t);
+            }
+            new_shares.push(share);
         }
         self.generated_shares = new_shares;
+        Ok(())
     }
 
     // - Steps:
-    //   1. Split shares into x and y vectors
-    //   2. Calculate Lagrange basis polynomials
-    //   3. Sum up the interpolation
-    //   4. Convert result back to u64
-    pub fn reconstruct_secret(&mut self, shares: &[(i64, i64)]) -> i64 {
-        let (x_values, y_values) = self.split_shares(shares);
-        let mut result = 0.0;
+    //   1. Check the shares can be combined, see `check_combinable`
+    //   2. Interpolate at x = 0 in the field, wiping partial sums
+    //   3. With commitments, check the result opens the first one
+    pub fn reconstruct_secret(&mut self, shares: &[Share<F>]) -> Result<F::Elem> {
+        let x_values = self.check_combinable(shares)?;
+        let zero = self.field.zero();
+        let mut result = self.interpolate(&x_values, shares, &zero, Share::expose_secret)?;
+
+        if self.is_committed() && !self.opens_commitment(&x_values, shares, &result)? {
+            result.wipe();
+            return Err(Error::CommitmentMismatch);
+        }
+        Ok(result)
+    }
+
+    // Value of the sharing polynomial at any `x`, with the same checks as
+    // `reconstruct_secret`. At x = 0 this is the secret.
+    pub fn interpolate_at(&self, shares: &[Share<F>], x: &F::Elem) -> Result<F::Elem> {
+        let x_values = self.check_combinable(shares)?;
+        self.interpolate(&x_values, shares, x, Share::expose_secret)
+    }
+
+    // Regenerates the share a participant at `index` lost, from any
+    // threshold of the others. Pedersen shares get their blinding back
+    // too, so the new share verifies against the same commitments.
+    pub fn recover_share(&self, shares: &[Share<F>], index: &F::Elem) -> Result<Share<F>> {
+        if self.field.is_zero(index) {
+            return Err(Error::ZeroIndex);
+        }
+        if !self.field.contains(index) {
+            return Err(Error::OutOfRange);
+        }
+        let x_values = self.check_combinable(shares)?;
+
+        let value = self.interpolate(&x_values, shares, index, Share::expose_secret)?;
+        let first = &shares[0];
+        let mut share = Share::new(
+            index.clone(),
+            value,
+            first.set_id,
+            first.threshold,
+            first.field_id.clone(),
+        );
+        if shares.iter().all(|share| share.expose_blinding().is_some()) {
+            let blinding = self.interpolate(&x_values, shares, index, |share| {
+                share.expose_blinding().expect("Checked above")
+            })?;
+            share = share.with_blinding(blinding);
+        }
+        Ok(share)
+    }
+
+    // The sharing polynomial, its constant term being the secret,
+    // interpolated through all the shares. One more coefficient than the
+    // threshold allows would mean the shares are inconsistent, so this
+    // is checked first like in `reconstruct_secret`.
+    pub fn recover_polynomial(&self, shares: &[Share<F>]) -> Result<Polynomial<F>> {
+        let x_values = self.check_combinable(shares)?;
+        let mut y_values = self.y_values(shares);
+        let polynomial = Polynomial::interpolate(self.field.clone(), &x_values, &y_values);
+        y_values.wipe();
+        polynomial
+    }
+
+    // Checks shares can be combined and returns their x values:
+    // 1. They form a usable set for this field
+    // 2. With commitments, every share verifies, or the bad ones are
+    //    named. Without, any shares beyond the threshold agree with the
+    //    rest (`reconstruct_checked` names the ones that don't).
+    fn check_combinable(&self, shares: &[Share<F>]) -> Result<Vec<F::Elem>> {
+        share::check_shares(&self.field, shares)?;
+
+        let x_values = self.x_values(shares);
+        if self.is_committed() {
+            let invalid = self.verify_shares(shares)?;
+            if !invalid.is_empty() {
+                return Err(Error::InvalidShares(invalid));
+            }
+        } else if shares.len() > shares[0].threshold {
+            let mut y_values = self.y_values(shares);
+            let consistent =
+                robust::is_consistent(&self.field, &x_values, &y_values, shares[0].threshold);
+            y_values.wipe();
+            if !consistent? {
+                return Err(Error::InconsistentShares);
+            }
+        }
+        Ok(x_values)
+    }
+
+    fn is_committed(&self) -> bool {
+        self.vss_commitments.is_some() || self.pedersen_commitments.is_some()
+    }
+
+    // Lagrange interpolation at `at` of the values `value` picks out of
+    // the shares
+    fn interpolate(
+        &self,
+        x_values: &[F::Elem],
+        shares: &[Share<F>],
+        at: &F::Elem,
+        value: impl Fn(&Share<F>) -> &F::Elem,
+    ) -> Result<F::Elem> {
+        let basis = polynomial::lagrange_coefficients(&self.field, x_values, at)?;
+        let mut result = self.field.zero();
+
+        for (share, basis) in shares.iter().zip(&basis) {
+            let mut term = self.field.mul(value(share), basis);
+            let sum = self.field.add(&result, &term);
+            replace_wiped(&mut result, sum);
+            term.wipe();
+        }
+
+        Ok(result)
+    }
+
+    // g^secret = C_0 for Feldman, g^secret h^blinding = C_0 for Pedersen
+    // with the blinding interpolated the same way as the secret
+    fn opens_commitment(
+        &self,
+        x_values: &[F::Elem],
+        shares: &[Share<F>],
+        secret: &F::Elem,
+    ) -> Result<bool> {
+        let Some(group) = &self.vss_params else {
+            return Err(Error::MissingCommitments);
+        };
+        let mut exponent = self.field.to_bigint(secret);
+
+        let opens = if let Some(commitments) = &self.pedersen_commitments {
+            let zero = self.field.zero();
+            let mut blinding = self.interpolate(x_values, shares, &zero, |share| {
+                share
+                    .expose_blinding()
+                    .expect("Pedersen shares were verified with their blinding")
+            })?;
+            let mut blinding_exponent = self.field.to_bigint(&blinding);
+            let opening = group.add(
+                &group.mul_generator(&exponent),
+                &group.mul(commitments.h(), &blinding_exponent),
+            );
+            blinding.wipe();
+            blinding_exponent.wipe();
+            commitments.points().first() == Some(&opening)
+        } else if let Some(commitments) = &self.vss_commitments {
+            commitments.public_key() == Some(&group.mul_generator(&exponent))
+        } else {
+            return Err(Error::MissingCommitments);
+        };
+
+        exponent.wipe();
+        Ok(opens)
+    }
+
+    // Error-correcting reconstruction for shares that can't be trusted,
+    // commitments or not. With n shares of a threshold-t sharing it
+    // recovers the secret while at most (n - t) / 2 of them are wrong,
+    // and returns the indices of the wrong ones alongside.
+    pub fn reconstruct_robust(&mut self, shares: &[Share<F>]) -> Result<(F::Elem, Vec<F::Elem>)> {
+        share::check_shares(&self.field, shares)?;
+
+        let x_values = self.x_values(shares);
+        let mut y_values = self.y_values(shares);
+        let decoded = robust::decode(&self.field, &x_values, &y_values, shares[0].threshold);
+        y_values.wipe();
+
+        let (polynomial, faulty) = decoded?;
+        Ok((polynomial.constant_term(), faulty))
+    }
+
+    // Cheater identification without commitments: the secret from the
+    // largest subset of shares lying on one polynomial of degree
+    // threshold - 1, and the indices of the shares on and off it. Beyond
+    // what `reconstruct_robust` corrects this searches every
+    // threshold-sized subset, and fails when no subset beats the others
+    // or when there are too many subsets to search.
+    pub fn reconstruct_checked(
+        &mut self,
+        shares: &[Share<F>],
+    ) -> Result<(F::Elem, ConsistencyReport<F>)> {
+        share::check_shares(&self.field, shares)?;
+
+        let x_values = self.x_values(shares);
+        let mut y_values = self.y_values(shares);
+        let found =
+            robust::largest_consistent(&self.field, &x_values, &y_values, shares[0].threshold);
+        y_values.wipe();
+
+        let (polynomial, report) = found?;
+        Ok((polynomial.constant_term(), report))
+    }
+
+    fn set_group(&mut self, group: G, hiding: bool) -> Result<()> {
+        group.validate()?;
+        if self.field.order() != group.order() {
+            return Err(Error::InvalidParams(
+                "sharing field must be the scalar field of the VSS group",
+            ));
+        }
+        // Switching modes after the fact would publish commitments of
+        // the other kind for the same polynomial, e.g. Feldman's
+        // C_0 = g^secret next to Pedersen commitments meant to hide it
+        if self.polynomial.is_some() || self.is_committed() {
+            return Err(Error::InvalidParams(
+                "commitments must be enabled before generating shares",
+            ));
+        }
+
+        self.vss_params = Some(group);
+        self.hiding = hiding;
+        Ok(())
+    }
+
+    // Generate Feldman or Pedersen commitments, if VSS is enabled. In
+    // Pedersen mode this waits until the blinding polynomial exists.
+    fn commit(&mut self) {
+        let (Some(group), Some(polynomial)) = (&self.vss_params, &self.polynomial) else {
+            return;
+        };
+        let mut exponents = self.exponents(polynomial);
+
+        if !self.hiding {
+            self.vss_commitments = Some(VSSCommitments::new(&exponents, group));
+            self.pedersen_commitments = None;
+        } else if let Some(blinding) = &self.blinding {
+            let mut blinding = self.exponents(blinding);
+            self.pedersen_commitments = Some(
+                PedersenCommitments::new(&exponents, &blinding, group)
+                    .expect("One blinding coefficient per coefficient"),
+            );
+            self.vss_commitments = None;
+            blinding.wipe();
+        }
+        exponents.wipe();
+    }
+
+    // One exponent per coefficient up to the threshold, so commitment
+    // vectors keep their length when top coefficients happen to be zero
+    fn exponents(&self, polynomial: &Polynomial<F>) -> Vec<BigInt> {
+        (0..self.threshold)
+            .map(|i| {
+                let mut coefficient = polynomial.coefficient(i);
+                let exponent = self.field.to_bigint(&coefficient);
+                coefficient.wipe();
+                exponent
+            })
+            .collect()
+    }
+
+    // Share belongs to this model and sits at a usable point
+    fn check_share(&self, share: &Share<F>) -> Result<()> {
+        if !self.owns(share) {
+            return Err(Error::MismatchedShares);
+        }
+        if self.field.is_zero(&share.index) {
+            return Err(Error::ZeroIndex);
+        }
+        if !self.field.contains(&share.index) || !self.field.contains(share.expose_secret()) {
+            return Err(Error::OutOfRange);
+        }
+        Ok(())
+    }
+
+    // Share was produced by this model
+    fn owns(&self, share: &Share<F>) -> bool {
+        share.set_id == self.set_id
+            && share.threshold == self.threshold
+            && share.field_id == self.field.id()
+    }
+
+    fn x_values(&self, shares: &[Share<F>]) -> Vec<F::Elem> {
+        shares.iter().map(|share| share.index.clone()).collect()
+    }
+
+    // Copies of the share values, the caller wipes them
+    fn y_values(&self, shares: &[Share<F>]) -> Vec<F::Elem> {
+        shares
+            .iter()
+            .map(|share| share.expose_secret().clone())
+            .collect()
+    }
+}
+
+impl<F: Field, G: Group> fmt::Debug for SharmirModel<F, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharmirModel")
+            .field("field", &self.field.id())
+            .field("shares", &self.shares)
+            .field("threshold", &self.threshold)
+            .field("set_id", &self.set_id)
+            .field(
+                "commitments",
+                &self.vss_params.as_ref().and_then(|group| {
+                    match (&self.vss_commitments, &self.pedersen_commitments) {
+                        (Some(commitments), _) => Some(commitments.fingerprint(group)),
+                        (_, Some(commitments)) => Some(commitments.fingerprint(group)),
+                        _ => None,
+                    }
+                }),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field, G: Group> Drop for SharmirModel<F, G> {
+    fn drop(&mut self) {
+        self.secret.wipe();
+        // The polynomials and each share wipe themselves
+        self.generated_shares.clear();
+    }
+}
 
-        for i in 0..shares.len() {
-            let (numerator, denominator) = self.lagrange_basis(i, &x_values);
-            result += y_values[i] as f64 * numerator / denominator;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curves::Ristretto255;
+    use crate::field::{Fp64, Gf256, Gf2_128};
+    use num_traits::{One, Zero};
+    use rand::rngs::OsRng;
+    use rand_chacha::ChaCha20Rng;
+
+    #[test]
+    fn reconstructs_secrets_of_any_size() {
+        let secrets = [
+            BigInt::zero(),
+            BigInt::from(123_456_789),
+            (BigInt::one() << 61) - 2,
+            // A 256-bit key lands in 2^521 - 1
+            BigInt::from_bytes_be(Sign::Plus, &[0xab; 32]),
+        ];
+        for secret in secrets {
+            let mut model = SharmirModel::new(secret.clone(), 5, 3).unwrap();
+            model.generate_shares(&mut OsRng);
+            let shares = model.get_shares().clone();
+            assert_eq!(model.reconstruct_secret(&shares[..3]), Ok(secret.clone()));
+            assert_eq!(model.reconstruct_secret(&shares[2..]), Ok(secret));
         }
+    }
+
+    #[test]
+    fn picks_the_smallest_mersenne_prime_above_the_secret() {
+        let mersenne = |e: u32| (BigInt::one() << e) - 1;
+        let modulus_for =
+            |secret: &BigInt| PrimeField::for_secret(secret).unwrap().modulus().clone();
+        assert_eq!(modulus_for(&BigInt::from(7)), mersenne(61));
+        assert_eq!(modulus_for(&mersenne(61)), mersenne(89));
+        assert_eq!(modulus_for(&(BigInt::one() << 125)), mersenne(127));
+
+        let model = SharmirModel::from_bytes(&[0xff; 16], 3, 2).unwrap();
+        assert_eq!(model.field().modulus(), &mersenne(521));
+    }
+
+    #[test]
+    fn shares_with_a_given_prime_modulus() {
+        let secret = BigInt::from(1000);
+        let mut model =
+            SharmirModel::with_modulus(secret.clone(), BigInt::from(1009), 4, 2).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+        assert!(shares
+            .iter()
+            .all(|share| *share.expose_secret() < BigInt::from(1009)));
+        assert_eq!(model.reconstruct_secret(&shares[1..3]), Ok(secret));
+    }
+
+    #[test]
+    fn rejects_unusable_modulus() {
+        for modulus in [0, 1] {
+            let model = SharmirModel::with_modulus(BigInt::from(0), BigInt::from(modulus), 4, 3);
+            assert!(matches!(model, Err(Error::InvalidParams(_))));
+        }
+    }
+
+    #[test]
+    fn rejects_composite_modulus() {
+        // Fermat inverses mod 15 would reconstruct 2 instead of 5
+        let model = SharmirModel::with_modulus(BigInt::from(5), BigInt::from(15), 4, 3);
+        assert!(matches!(model, Err(Error::InvalidParams(_))));
 
-        result.round() as i64
+        let mut model =
+            SharmirModel::with_modulus(BigInt::from(5), BigInt::from(17), 4, 3).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+        assert_eq!(model.reconstruct_secret(&shares[1..]), Ok(BigInt::from(5)));
     }
 
-    fn split_shares(&self, shares: &[(i64, i64)]) -> (Vec<i64>, Vec<i64>) {
-        let x_values: Vec<i64> = shares.iter().map(|&(x, _)| x).collect();
-        let y_values: Vec<i64> = shares.iter().map(|&(_, y)| y).collect();
-        (x_values, y_values)
+    #[test]
+    fn shares_over_small_and_binary_fields() {
+        let fp64 = Fp64::default_prime();
+        let secret = fp64.elem_from_u64(42);
+        let mut model = SharmirModel::with_field(fp64, secret, 4, 3).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+        assert_eq!(model.reconstruct_secret(&shares[1..]), Ok(secret));
+
+        let mut model = SharmirModel::with_field(Gf2_128, 0xdead_beef << 64, 4, 3).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+        assert_eq!(
+            model.reconstruct_secret(&shares[..3]),
+            Ok(0xdead_beef << 64)
+        );
     }
 
-    fn lagrange_basis(&self, share_index: usize, x_values: &[i64]) -> (f64, f64) {
-        let mut numerator = 1.0;
-        let mut denominator = 1.0;
+    #[test]
+    fn shares_carry_their_sharing_metadata() {
+        let mut model = SharmirModel::new(BigInt::from(77), 3, 2).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+        for share in &shares {
+            assert_eq!(share.set_id, model.set_id());
+            assert_eq!(share.threshold, 2);
+            assert_eq!(share.field_id, model.field().id());
+        }
+
+        let mut other = SharmirModel::new(BigInt::from(77), 3, 2).unwrap();
+        other.generate_shares(&mut OsRng);
+        let foreign = other.get_shares()[0].clone();
+        assert_eq!(model.verify_share(&foreign), Err(Error::MismatchedShares));
+        assert_eq!(
+            model.reconstruct_secret(&[shares[1].clone(), foreign]),
+            Err(Error::MismatchedShares)
+        );
+    }
 
-        for (index, &current_x) in x_values.iter().enumerate() {
-            if index != share_index {
-                numerator *= current_x as f64;
-                denominator *= (current_x - x_values[share_index]) as f64;
+    #[test]
+    fn rejects_bad_parameters() {
+        assert_eq!(
+            SharmirModel::new(BigInt::from(5), 3, 4).unwrap_err(),
+            Error::InvalidThreshold {
+                threshold: 4,
+                shares: 3
             }
+        );
+        assert_eq!(
+            SharmirModel::new(BigInt::from(5), 3, 0).unwrap_err(),
+            Error::InvalidThreshold {
+                threshold: 0,
+                shares: 3
+            }
+        );
+        assert_eq!(
+            SharmirModel::with_field(Gf256, 5, 256, 2).unwrap_err(),
+            Error::TooManyShares {
+                shares: 256,
+                max: 255
+            }
+        );
+        assert_eq!(
+            SharmirModel::with_modulus(BigInt::from(1009), BigInt::from(1009), 3, 2).unwrap_err(),
+            Error::OutOfRange
+        );
+    }
+
+    #[test]
+    fn rejects_unusable_share_sets() {
+        let mut model = SharmirModel::new(BigInt::from(31337), 5, 3).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+
+        assert_eq!(
+            model.reconstruct_secret(&shares[..2]),
+            Err(Error::InsufficientShares { needed: 3, got: 2 })
+        );
+        assert_eq!(
+            model.reconstruct_secret(&[]),
+            Err(Error::InsufficientShares { needed: 1, got: 0 })
+        );
+
+        let repeated = [shares[0].clone(), shares[1].clone(), shares[0].clone()];
+        assert_eq!(
+            model.reconstruct_secret(&repeated),
+            Err(Error::DuplicateIndex)
+        );
+
+        let mut at_zero = shares[..3].to_vec();
+        at_zero[2].index = BigInt::zero();
+        assert_eq!(model.reconstruct_secret(&at_zero), Err(Error::ZeroIndex));
+
+        let mut too_big = shares[..3].to_vec();
+        let first = &shares[0];
+        let value = model.field().modulus() + 1;
+        too_big[0] = Share::new(
+            first.index.clone(),
+            value,
+            first.set_id,
+            3,
+            first.field_id.clone(),
+        );
+        assert_eq!(model.reconstruct_secret(&too_big), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn shares_at_explicit_indices() {
+        let field = Fp64::new(65537);
+        let names = ["alice", "bob", "carol"];
+        let indices: Vec<_> = names
+            .iter()
+            .map(|name| share::index_for_name(&field, name).unwrap())
+            .collect();
+        let secret = field.elem_from_u64(4242);
+        let mut model = SharmirModel::with_field(field, secret, 3, 2).unwrap();
+        model.generate_shares_at(&indices, &mut OsRng).unwrap();
+        let shares = model.get_shares().clone();
+        assert!(shares
+            .iter()
+            .zip(&indices)
+            .all(|(share, x)| share.index == *x));
+        assert_eq!(model.reconstruct_secret(&shares[1..]), Ok(secret));
+
+        let one = field.one();
+        let two = field.add(&one, &one);
+        let cases = [
+            (
+                vec![one, two],
+                Error::IndexCountMismatch {
+                    expected: 3,
+                    got: 2,
+                },
+            ),
+            (vec![one, field.zero(), two], Error::ZeroIndex),
+            (vec![one, two, one], Error::DuplicateIndex),
+        ];
+        for (indices, error) in cases {
+            assert_eq!(model.generate_shares_at(&indices, &mut OsRng), Err(error));
+        }
+    }
+
+    #[test]
+    fn seeded_rng_reproduces_shares() {
+        let model = |seed: u64| {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            let mut model = SharmirModel::new(BigInt::from(143), 5, 3).unwrap();
+            model.generate_shares(&mut rng);
+            model
+        };
+        let (mut first, mut second) = (model(7), model(7));
+        assert_eq!(first.set_id(), second.set_id());
+        let values = |model: &mut SharmirModel<PrimeField>| {
+            model
+                .get_shares()
+                .iter()
+                .map(|share| (share.index.clone(), share.expose_secret().clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(&mut first), values(&mut second));
+
+        let mut other = model(8);
+        assert_ne!(other.set_id(), first.set_id());
+        assert_ne!(values(&mut other), values(&mut first));
+    }
+
+    #[test]
+    fn debug_leaves_out_the_secret() {
+        let secret = BigInt::from(0x5ec2e7u32);
+        let mut model = SharmirModel::new(secret.clone(), 3, 2).unwrap();
+        model.generate_shares(&mut OsRng);
+
+        let debug = format!("{:?}", model);
+        assert!(!debug.contains(&secret.to_string()));
+        assert!(debug.contains("threshold: 2"));
+        for share in model.get_shares() {
+            let value = share.expose_secret().to_string();
+            assert!(!debug.contains(&value));
+            assert!(!format!("{:?}", share).contains(&value));
+        }
+    }
+
+    #[test]
+    fn feldman_shares_verify_in_z_q() {
+        let group = VSSParams::new();
+        let mut model = SharmirModel::with_vss(BigInt::from(1000), 5, 3, group.clone()).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+        for share in &shares {
+            assert!(share.expose_secret() < group.q());
+            assert_eq!(model.verify_share(share), Ok(true));
+        }
+        let forged = Share::new(
+            shares[0].index.clone(),
+            (shares[0].expose_secret() + 1u32) % group.q(),
+            model.set_id(),
+            3,
+            model.field().id(),
+        );
+        assert_eq!(model.verify_share(&forged), Ok(false));
+        assert_eq!(
+            model.reconstruct_secret(&shares[2..]),
+            Ok(BigInt::from(1000))
+        );
+
+        // The secret has to fit in Z_q, and other fields can't commit
+        let too_big = SharmirModel::with_vss(group.q().clone(), 5, 3, group.clone());
+        assert_eq!(too_big.err(), Some(Error::OutOfRange));
+        let mut mersenne = SharmirModel::new(BigInt::from(1000), 5, 3).unwrap();
+        assert!(matches!(
+            mersenne.enable_vss(group),
+            Err(Error::InvalidParams(_))
+        ));
+    }
+
+    #[test]
+    fn commitment_mode_is_fixed_by_the_first_share() {
+        let mut model = SharmirModel::with_vss(BigInt::from(143), 5, 3, Ristretto255).unwrap();
+        model.generate_shares(&mut OsRng);
+        for switched in [
+            model.enable_pedersen(Ristretto255),
+            model.enable_vss(Ristretto255),
+        ] {
+            assert!(matches!(switched, Err(Error::InvalidParams(_))));
         }
+        assert!(model.pedersen_commitments().is_none());
+
+        let mut model = SharmirModel::with_pedersen(BigInt::from(143), 5, 3, Ristretto255).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+        assert!(model.vss_commitments().is_none());
+        for share in &shares {
+            assert!(share.expose_blinding().is_some());
+            assert_eq!(model.verify_share(share), Ok(true));
+        }
+
+        // A share without its blinding can't be checked
+        let bare = Share::new(
+            shares[0].index.clone(),
+            shares[0].expose_secret().clone(),
+            model.set_id(),
+            3,
+            model.field().id(),
+        );
+        assert_eq!(model.verify_share(&bare), Err(Error::MissingBlinding));
+        assert_eq!(
+            model.reconstruct_secret(&shares[..3]),
+            Ok(BigInt::from(143))
+        );
+    }
+
+    #[test]
+    fn verify_shares_names_forged_shares_in_both_modes() {
+        let feldman = SharmirModel::with_vss(BigInt::from(31), 6, 3, Ristretto255).unwrap();
+        let pedersen = SharmirModel::with_pedersen(BigInt::from(31), 6, 3, Ristretto255).unwrap();
+        for mut model in [feldman, pedersen] {
+            model.generate_shares(&mut OsRng);
+            let mut shares = model.get_shares().clone();
+            assert_eq!(model.verify_shares(&shares), Ok(vec![]));
+
+            let forged = Share::new(
+                shares[3].index.clone(),
+                shares[3].expose_secret() + 1u32,
+                model.set_id(),
+                3,
+                model.field().id(),
+            );
+            shares[3] = match shares[3].expose_blinding() {
+                Some(blinding) => forged.with_blinding(blinding.clone()),
+                None => forged,
+            };
+            assert_eq!(model.verify_shares(&shares), Ok(vec![3]));
+        }
+    }
+
+    #[test]
+    fn combiner_checks_shares_and_secret_against_received_commitments() {
+        let group = VSSParams::new();
+        let mut dealer = SharmirModel::with_vss(BigInt::from(500), 5, 3, group.clone()).unwrap();
+        dealer.generate_shares(&mut OsRng);
+        let mut shares = dealer.get_shares().clone();
+        let points = dealer.vss_commitments().unwrap().points().to_vec();
+
+        let received = VSSCommitments::from_points(points.clone());
+        let mut combiner =
+            SharmirModel::from_vss_commitments(received, dealer.set_id(), group.clone()).unwrap();
+        assert_eq!(
+            combiner.reconstruct_secret(&shares[1..4]),
+            Ok(BigInt::from(500))
+        );
+        assert!(matches!(
+            combiner.generate_shares_at(&[BigInt::from(9)], &mut OsRng),
+            Err(Error::InvalidParams(_))
+        ));
+
+        let tampered = (shares[3].expose_secret() + 1u32) % group.q();
+        shares[3] = Share::new(
+            shares[3].index.clone(),
+            tampered,
+            shares[3].set_id,
+            3,
+            shares[3].field_id.clone(),
+        );
+        assert_eq!(
+            combiner.reconstruct_secret(&shares),
+            Err(Error::InvalidShares(vec![3]))
+        );
+
+        // -C_0 and -C_1 leave the group. The two -1s cancel at odd x, so
+        // shares 1, 3 and 5 still verify one by one, but they
+        // interpolate to a secret that doesn't open -C_0.
+        let mut forged = points;
+        forged[0] = group.p() - &forged[0];
+        forged[1] = group.p() - &forged[1];
+        let mut combiner = SharmirModel::from_vss_commitments(
+            VSSCommitments::from_points(forged),
+            dealer.set_id(),
+            group,
+        )
+        .unwrap();
+        let odd = [shares[0].clone(), shares[2].clone(), shares[4].clone()];
+        assert_eq!(
+            combiner.reconstruct_secret(&odd),
+            Err(Error::CommitmentMismatch)
+        );
+    }
+
+    #[test]
+    fn combiner_from_pedersen_commitments() {
+        let mut dealer = SharmirModel::with_pedersen(BigInt::from(77), 4, 2, Ristretto255).unwrap();
+        dealer.generate_shares(&mut OsRng);
+        let shares = dealer.get_shares().clone();
+        let points = dealer.pedersen_commitments().unwrap().points().to_vec();
+
+        let received = PedersenCommitments::from_points(points, &Ristretto255);
+        let mut combiner =
+            SharmirModel::from_pedersen_commitments(received, dealer.set_id(), Ristretto255)
+                .unwrap();
+        assert_eq!(
+            combiner.reconstruct_secret(&shares[2..]),
+            Ok(BigInt::from(77))
+        );
+        // Pedersen mode is fixed, as for a dealer
+        assert!(combiner.enable_vss(Ristretto255).is_err());
+
+        // A blinding from another share fails that share only
+        let swapped = Share::new(
+            shares[1].index.clone(),
+            shares[1].expose_secret().clone(),
+            shares[1].set_id,
+            2,
+            shares[1].field_id.clone(),
+        )
+        .with_blinding(shares[0].expose_blinding().unwrap().clone());
+        let quorum = [shares[0].clone(), swapped];
+        assert_eq!(
+            combiner.reconstruct_secret(&quorum),
+            Err(Error::InvalidShares(vec![1]))
+        );
+    }
+
+    #[test]
+    fn robust_reconstruction_names_share_indices() {
+        let field = Fp64::default_prime();
+        let secret = field.elem_from_u64(4242);
+        let mut model = SharmirModel::with_field(field, secret, 7, 3).unwrap();
+        let indices: Vec<_> = [3, 14, 15, 92, 65, 35, 89]
+            .map(|x| field.elem_from_u64(x))
+            .to_vec();
+        model.generate_shares_at(&indices, &mut OsRng).unwrap();
+        let mut shares = model.get_shares().clone();
+        for position in [1, 5] {
+            let wrong = field.add(shares[position].expose_secret(), &field.one());
+            shares[position] = Share::new(indices[position], wrong, model.set_id(), 3, field.id());
+        }
+
+        let (recovered, faulty) = model.reconstruct_robust(&shares).unwrap();
+        assert_eq!(recovered, secret);
+        assert_eq!(faulty, vec![indices[1], indices[5]]);
+
+        // A third is one more than (7 - 3) / 2
+        let wrong = field.add(shares[3].expose_secret(), &field.one());
+        shares[3] = Share::new(indices[3], wrong, model.set_id(), 3, field.id());
+        assert_eq!(
+            model.reconstruct_robust(&shares).err(),
+            Some(Error::TooManyFaults { tolerated: 2 })
+        );
+    }
+
+    #[test]
+    fn checked_reconstruction_finds_cheaters_past_decoding() {
+        let mut model = SharmirModel::new(BigInt::from(2024), 7, 3).unwrap();
+        // Hashed indices, at 1..=7 the symmetric pairs let a wrong
+        // polynomial through as many points as the right one
+        let names = ["ada", "bo", "cy", "di", "ed", "flo", "gus"];
+        let indices: Vec<BigInt> = names
+            .iter()
+            .map(|name| share::index_for_name(model.field(), name).unwrap())
+            .collect();
+        model.generate_shares_at(&indices, &mut OsRng).unwrap();
+        let mut shares = model.get_shares().clone();
+        assert_eq!(model.reconstruct_secret(&shares), Ok(BigInt::from(2024)));
+
+        // Three cheaters, one more than (7 - 3) / 2
+        for position in [0, 2, 6] {
+            let share = &shares[position];
+            let lie = Share::new(
+                share.index.clone(),
+                (share.expose_secret() + 17u32) % model.field().modulus(),
+                share.set_id,
+                3,
+                share.field_id.clone(),
+            );
+            shares[position] = lie;
+        }
+        assert_eq!(
+            model.reconstruct_secret(&shares),
+            Err(Error::InconsistentShares)
+        );
+        assert!(matches!(
+            model.reconstruct_robust(&shares),
+            Err(Error::TooManyFaults { tolerated: 2 })
+        ));
+
+        let (secret, report) = model.reconstruct_checked(&shares).unwrap();
+        assert_eq!(secret, BigInt::from(2024));
+        let named = |positions: &[usize]| -> Vec<BigInt> {
+            positions.iter().map(|&p| indices[p].clone()).collect()
+        };
+        assert_eq!(report.inconsistent, named(&[0, 2, 6]));
+        assert_eq!(report.consistent, named(&[1, 3, 4, 5]));
+    }
+
+    #[test]
+    fn recovers_lost_shares_and_the_polynomial() {
+        let field = Fp64::new(65537);
+        let secret = field.elem_from_u64(1234);
+        let mut model = SharmirModel::with_field(field, secret, 5, 3).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+
+        let recovered = model
+            .recover_share(&shares[1..4], &shares[0].index)
+            .unwrap();
+        assert_eq!(recovered.expose_secret(), shares[0].expose_secret());
+        assert!(recovered.is_compatible(&shares[0]));
+        assert!(recovered.expose_blinding().is_none());
+        assert_eq!(
+            model.recover_share(&shares[1..4], &field.zero()).err(),
+            Some(Error::ZeroIndex)
+        );
+
+        let polynomial = model.recover_polynomial(&shares).unwrap();
+        assert_eq!(polynomial.constant_term(), secret);
+        assert!(polynomial.degree().is_some_and(|d| d < 3));
+        for share in &shares {
+            assert_eq!(&polynomial.evaluate(&share.index), share.expose_secret());
+        }
+
+        let at = field.elem_from_u64(100);
+        assert_eq!(
+            model.interpolate_at(&shares[2..], &at),
+            Ok(polynomial.evaluate(&at))
+        );
+        assert_eq!(
+            model.interpolate_at(&shares[..3], &field.zero()),
+            Ok(secret)
+        );
+        assert_eq!(
+            model.interpolate_at(&shares[..2], &at),
+            Err(Error::InsufficientShares { needed: 3, got: 2 })
+        );
+    }
+
+    #[test]
+    fn recovered_pedersen_share_keeps_its_blinding() {
+        let mut model = SharmirModel::with_pedersen(BigInt::from(5), 4, 3, Ristretto255).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+
+        let recovered = model.recover_share(&shares[..3], &shares[3].index).unwrap();
+        assert_eq!(recovered.expose_secret(), shares[3].expose_secret());
+        assert_eq!(recovered.expose_blinding(), shares[3].expose_blinding());
+        assert_eq!(model.verify_share(&recovered), Ok(true));
 
-        (numerator, denominator)
+        // A new participant at x = 9 gets a share that verifies as well
+        let joined = model.recover_share(&shares[1..], &BigInt::from(9)).unwrap();
+        assert_eq!(model.verify_share(&joined), Ok(true));
     }
 }
@@ -0,0 +1,237 @@
+use std::fmt;
+
+use rand::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::field::{Field, Wipe};
+use crate::hash;
+
+// Random tag shared by every share of one sharing, so shares from
+// different runs can't be mixed by accident
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SetId([u8; 16]);
+
+impl SetId {
+    pub fn random<R: RngCore + ?Sized>(rng: &mut R) -> Self {
+        let mut bytes = [0u8; 16];
+        rng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl fmt::Debug for SetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+// One participant's share: the point (index, value) on the sharing
+// polynomial plus the metadata needed to combine it safely. Pedersen
+// sharings add the blinding polynomial's value at the same index.
+// Both values are only reachable through the `expose_` accessors.
+// `V` is a vector of values for byte-mode shares, one per secret byte.
+#[derive(Clone)]
+pub struct Share<F: Field, V: Wipe = <F as Field>::Elem> {
+    pub index: F::Elem,
+    value: V,
+    blinding: Option<V>,
+    pub set_id: SetId,
+    pub threshold: usize,
+    pub field_id: String,
+}
+
+impl<F: Field, V: Wipe> fmt::Debug for Share<F, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Share")
+            .field("index", &self.index)
+            .field("value", &"<redacted>")
+            .field("blinding", &self.blinding.as_ref().map(|_| "<redacted>"))
+            .field("set_id", &self.set_id)
+            .field("threshold", &self.threshold)
+            .field("field_id", &self.field_id)
+            .finish()
+    }
+}
+
+// The values are secret material, the index and metadata are not
+impl<F: Field, V: Wipe> Drop for Share<F, V> {
+    fn drop(&mut self) {
+        self.value.wipe();
+        if let Some(blinding) = &mut self.blinding {
+            blinding.wipe();
+        }
+    }
+}
+
+impl<F: Field, V: Wipe> Share<F, V> {
+    pub fn new(
+        index: F::Elem,
+        value: V,
+        set_id: SetId,
+        threshold: usize,
+        field_id: String,
+    ) -> Self {
+        Self {
+            index,
+            value,
+            blinding: None,
+            set_id,
+            threshold,
+            field_id,
+        }
+    }
+
+    // Attaches the Pedersen blinding value t_i
+    pub fn with_blinding(mut self, blinding: V) -> Self {
+        self.blinding = Some(blinding);
+        self
+    }
+
+    pub fn expose_secret(&self) -> &V {
+        &self.value
+    }
+
+    pub fn expose_blinding(&self) -> Option<&V> {
+        self.blinding.as_ref()
+    }
+
+    // Same sharing, same threshold and same field
+    pub fn is_compatible(&self, other: &Share<F, V>) -> bool {
+        self.set_id == other.set_id
+            && self.threshold == other.threshold
+            && self.field_id == other.field_id
+    }
+}
+
+// Hashes tried by `index_for_name` before giving up. With the spare
+// bits cleared each one is an element with probability above 1/2.
+const MAX_INDEX_ATTEMPTS: u32 = 256;
+
+// Deterministic non-zero x-coordinate for a participant name, so every
+// party can work out the indices from the roster alone. Hashes the name
+// with a counter until the digest, cut to the length of the field's
+// element encoding, is a non-zero element. Names that collide surface
+// as DuplicateIndex when the shares are generated.
+pub fn index_for_name<F: Field>(field: &F, name: &str) -> Result<F::Elem> {
+    let length = field.elem_to_bytes(&field.zero()).len();
+    let bits = (field.order() - 1u32).bits() as usize;
+    // Leading bits of the encoding that are zero in every element
+    let spare = (length * 8).saturating_sub(bits);
+
+    for counter in 0..MAX_INDEX_ATTEMPTS {
+        let mut bytes = hash::expand(
+            b"shamir-participant-index",
+            counter,
+            name.as_bytes(),
+            length,
+        );
+        // Clear the bits above the field size so rejection stays rare
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let cleared = spare.saturating_sub(i * 8).min(8) as u32;
+            *byte &= 0xffu8.checked_shr(cleared).unwrap_or(0);
+        }
+
+        if let Some(index) = field.elem_from_bytes(&bytes) {
+            if !field.is_zero(&index) {
+                return Ok(index);
+            }
+        }
+    }
+    Err(Error::InvalidParams(
+        "could not derive a share index from the name",
+    ))
+}
+
+// Checks `shares` can be combined: all from one sharing over `field`,
+// at least `threshold` of them, distinct non-zero indices and values
+// inside the field
+pub fn check_shares<F: Field>(field: &F, shares: &[Share<F>]) -> Result<()> {
+    check_metadata(field, shares)?;
+    if !shares
+        .iter()
+        .all(|share| field.contains(share.expose_secret()))
+    {
+        return Err(Error::OutOfRange);
+    }
+    Ok(())
+}
+
+// `check_shares` minus the values, for shares whose values aren't
+// single field elements
+pub fn check_metadata<F: Field, V: Wipe>(field: &F, shares: &[Share<F, V>]) -> Result<()> {
+    let first = shares
+        .first()
+        .ok_or(Error::InsufficientShares { needed: 1, got: 0 })?;
+
+    if shares
+        .iter()
+        .any(|share| share.field_id != field.id() || !share.is_compatible(first))
+    {
+        return Err(Error::MismatchedShares);
+    }
+    if shares.len() < first.threshold {
+        return Err(Error::InsufficientShares {
+            needed: first.threshold,
+            got: shares.len(),
+        });
+    }
+
+    for (i, share) in shares.iter().enumerate() {
+        if field.is_zero(&share.index) {
+            return Err(Error::ZeroIndex);
+        }
+        if !field.contains(&share.index) {
+            return Err(Error::OutOfRange);
+        }
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(Error::DuplicateIndex);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{Fp64, Gf256, PrimeField};
+    use num_bigint::BigInt;
+
+    #[test]
+    fn index_for_name_is_stable_and_non_zero() {
+        let field = Fp64::default_prime();
+        let names: Vec<String> = (0..100).map(|i| format!("participant-{}", i)).collect();
+        let indices: Vec<_> = names
+            .iter()
+            .map(|name| index_for_name(&field, name).unwrap())
+            .collect();
+        for (name, index) in names.iter().zip(&indices) {
+            assert!(!field.is_zero(index));
+            assert_eq!(index_for_name(&field, name).as_ref(), Ok(index));
+        }
+        let mut distinct = indices.clone();
+        distinct.sort_by_key(|index| field.canonical(index));
+        distinct.dedup();
+        assert_eq!(distinct.len(), indices.len());
+    }
+
+    #[test]
+    fn index_for_name_fits_narrow_and_odd_sized_fields() {
+        // A byte per index, so only the zero byte is ever rejected
+        let alice = index_for_name(&Gf256, "alice").unwrap();
+        assert_ne!(alice, 0);
+        assert_ne!(index_for_name(&Gf256, "bob"), Ok(alice));
+
+        // 17 bits in three bytes, the spare bits keep retries rare
+        let field = PrimeField::new(BigInt::from(65537));
+        let index = index_for_name(&field, "alice").unwrap();
+        assert!(field.contains(&index) && !field.is_zero(&index));
+    }
+}
@@ -1,53 +1,794 @@
-use num_bigint::{BigInt, RandBigInt};
+use std::fmt::Debug;
+use std::sync::{Arc, OnceLock};
+
+use num_bigint::{BigInt, RandBigInt, Sign};
 use num_traits::{One, Zero};
+use rand::prelude::*;
+use rand::rngs::OsRng;
 use rand::thread_rng;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+use crate::field::{replace_wiped, Field, Wipe};
+use crate::groups;
+use crate::hash;
+use crate::multiexp::{self, FixedBase};
+use crate::primes;
+use crate::share::Share;
+
+// Smallest modulus `generate` accepts, anything below is only for tests
+const MIN_GENERATED_BITS: u64 = 64;
+
+// Size of the random weights in batch verification, a batch containing a
+// bad share passes with probability at most 2^-128
+const BATCH_WEIGHT_BITS: u64 = 128;
+
+// Minimum sizes `VSSParams::validate` accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecurityPolicy {
+    pub min_p_bits: u64,
+    pub min_q_bits: u64,
+}
 
+impl Default for SecurityPolicy {
+    // 112-bit security per NIST SP 800-57
+    fn default() -> Self {
+        Self {
+            min_p_bits: 2048,
+            min_q_bits: 224,
+        }
+    }
+}
+
+impl SecurityPolicy {
+    // Accepts any size, only for the toy group and tests
+    pub fn none() -> Self {
+        Self {
+            min_p_bits: 0,
+            min_q_bits: 0,
+        }
+    }
+}
+
+// The group is fixed at construction, so the (expensive) validation
+// result and the generator table can be cached. Clones share the table.
 #[derive(Debug, Clone)]
 pub struct VSSParams {
-    pub p: BigInt, // Large prime
-    pub q: BigInt, // Prime divisor of p-1
-    pub g: BigInt, // Generator of order q
+    p: BigInt, // Large prime
+    q: BigInt, // Prime divisor of p-1
+    g: BigInt, // Generator of order q
+    policy: SecurityPolicy,
+    // One of the RFC groups, whose structure needs no checking
+    named: bool,
+    validated: OnceLock<Result<()>>,
+    generator_table: OnceLock<Arc<FixedBase<VSSParams>>>,
+}
+
+// Prime-order group the coefficients are committed in, written
+// additively: `add` is the group operation (multiplication mod p for
+// `VSSParams`) and `mul` raises a point to a scalar. Scalars live in
+// Z_order, so the sharing field has to be exactly that.
+pub trait Group: Clone + Debug {
+    type Point: Clone + Debug + PartialEq;
+
+    fn id(&self) -> String;
+    fn order(&self) -> BigInt;
+    fn generator(&self) -> Self::Point;
+    fn identity(&self) -> Self::Point;
+    fn add(&self, a: &Self::Point, b: &Self::Point) -> Self::Point;
+    // `scalar` is reduced mod the order first
+    fn mul(&self, point: &Self::Point, scalar: &BigInt) -> Self::Point;
+    // Canonical encoding, fixed-length per group
+    fn point_to_bytes(&self, point: &Self::Point) -> Vec<u8>;
+    // None unless `bytes` encodes an element of the prime-order group
+    fn point_from_bytes(&self, bytes: &[u8]) -> Option<Self::Point>;
+    // Point nobody knows the discrete log of relative to the generator,
+    // derived from `message` so every party can recompute it
+    fn hash_to_point(&self, message: &[u8]) -> Self::Point;
+
+    // Whether `point` lies in the prime-order group. Curve point types
+    // can't hold anything else, a mod-p group's residues can.
+    fn is_element(&self, _point: &Self::Point) -> bool {
+        true
+    }
+
+    fn mul_generator(&self, scalar: &BigInt) -> Self::Point {
+        self.mul(&self.generator(), scalar)
+    }
+
+    // prod points[i]^scalars[i], backends with a native multiscalar
+    // multiplication override this
+    fn multi_exp(&self, points: &[Self::Point], scalars: &[BigInt]) -> Self::Point {
+        multiexp::multi_exp(self, points, scalars)
+    }
+
+    // Standard curves are fixed, only parameters from outside need checks
+    fn validate(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
-pub struct VSSCommitments {
-    commitments: Vec<BigInt>,
+pub struct VSSCommitments<G: Group = VSSParams> {
+    commitments: Vec<G::Point>,
 }
 
 impl VSSParams {
+    // Toy group for demos, offers no security and carries a policy that
+    // lets it through validation. Use a named group or `generate` for
+    // anything real.
     pub fn new() -> Self {
-        // TODO Change to larger primes
         let p = BigInt::parse_bytes(b"2039", 10).unwrap(); // Example prime
         let q = BigInt::parse_bytes(b"1019", 10).unwrap(); // (p-1)/2
         let g = BigInt::from(2); // Generator
 
-        Self { p, q, g }
+        Self::from_parts(p, q, g).with_policy(SecurityPolicy::none())
+    }
+
+    // Parameters from an untrusted source, checked under the default
+    // policy before any verification uses them
+    pub fn from_parts(p: BigInt, q: BigInt, g: BigInt) -> Self {
+        Self {
+            p,
+            q,
+            g,
+            policy: SecurityPolicy::default(),
+            named: false,
+            validated: OnceLock::new(),
+            generator_table: OnceLock::new(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: SecurityPolicy) -> Self {
+        self.policy = policy;
+        self.reset_validation();
+        self
+    }
+
+    pub fn p(&self) -> &BigInt {
+        &self.p
+    }
+
+    pub fn q(&self) -> &BigInt {
+        &self.q
+    }
+
+    pub fn g(&self) -> &BigInt {
+        &self.g
+    }
+
+    pub fn policy(&self) -> SecurityPolicy {
+        self.policy
+    }
+
+    // Checks the group is what it claims to be: p and q prime, q | p - 1,
+    // g of order exactly q, and both sizes meeting the policy. Cheap
+    // checks run first, the result is cached after the first call.
+    pub fn validate(&self) -> Result<()> {
+        self.validated.get_or_init(|| self.check()).clone()
+    }
+
+    fn check(&self) -> Result<()> {
+        let one = BigInt::one();
+        if self.p <= BigInt::from(3) || !self.p.bit(0) || self.q <= one {
+            return Err(Error::InvalidParams("p must be an odd prime and q > 1"));
+        }
+        self.check_policy()?;
+        if !((&self.p - 1u32) % &self.q).is_zero() {
+            return Err(Error::InvalidParams("q does not divide p - 1"));
+        }
+        if self.g <= one || self.g >= self.p {
+            return Err(Error::InvalidParams("g must be in 2..p"));
+        }
+        // q prime and g != 1 makes the order exactly q
+        if !self.g.modpow(&self.q, &self.p).is_one() {
+            return Err(Error::InvalidParams("g does not have order q"));
+        }
+        if !primes::is_probable_prime(&self.q, &mut OsRng) {
+            return Err(Error::InvalidParams("q is not prime"));
+        }
+        if !primes::is_probable_prime(&self.p, &mut OsRng) {
+            return Err(Error::InvalidParams("p is not prime"));
+        }
+        Ok(())
+    }
+
+    fn check_policy(&self) -> Result<()> {
+        if self.p.bits() < self.policy.min_p_bits || self.q.bits() < self.policy.min_q_bits {
+            return Err(Error::InvalidParams(
+                "group is below the configured security level",
+            ));
+        }
+        Ok(())
+    }
+
+    // Named groups are fixed public constants like the curves, so their
+    // validation is settled up front with the policy check only. Running
+    // the primality tests on them would cost seconds per construction.
+    fn reset_validation(&mut self) {
+        self.validated = if self.named {
+            OnceLock::from(self.check_policy())
+        } else {
+            OnceLock::new()
+        };
+    }
+
+    // Named groups from RFC 3526 and RFC 7919, so every party in a
+    // deployment agrees on the parameters without exchanging them.
+    // At 1536 bits this one is below the default policy and only
+    // validates with a weaker one passed to `with_policy`.
+    pub fn modp_1536() -> Self {
+        Self::from_safe_prime(groups::MODP_1536)
+    }
+
+    // The default policy accepts these and everything below
+    pub fn modp_2048() -> Self {
+        Self::from_safe_prime(groups::MODP_2048)
+    }
+
+    pub fn modp_3072() -> Self {
+        Self::from_safe_prime(groups::MODP_3072)
+    }
+
+    pub fn modp_4096() -> Self {
+        Self::from_safe_prime(groups::MODP_4096)
+    }
+
+    pub fn modp_6144() -> Self {
+        Self::from_safe_prime(groups::MODP_6144)
+    }
+
+    pub fn modp_8192() -> Self {
+        Self::from_safe_prime(groups::MODP_8192)
+    }
+
+    pub fn ffdhe2048() -> Self {
+        Self::from_safe_prime(groups::FFDHE_2048)
+    }
+
+    pub fn ffdhe3072() -> Self {
+        Self::from_safe_prime(groups::FFDHE_3072)
+    }
+
+    pub fn ffdhe4096() -> Self {
+        Self::from_safe_prime(groups::FFDHE_4096)
+    }
+
+    pub fn ffdhe6144() -> Self {
+        Self::from_safe_prime(groups::FFDHE_6144)
+    }
+
+    pub fn ffdhe8192() -> Self {
+        Self::from_safe_prime(groups::FFDHE_8192)
+    }
+
+    // q = (p - 1) / 2 and g = 2, which has order q since p = 7 mod 8
+    fn from_safe_prime(hex: &str) -> Self {
+        let p = BigInt::parse_bytes(hex.as_bytes(), 16).expect("Group primes are valid hex");
+        let q = (&p - 1u32) >> 1;
+        let g = BigInt::from(2);
+
+        let mut params = Self::from_parts(p, q, g);
+        params.named = true;
+        params.reset_validation();
+        params
+    }
+
+    // Fresh group: a random safe prime p = 2q + 1 of `bits` bits and a
+    // generator of the order-q subgroup. 2048 or 3072 bits for
+    // production, expect this to take a while at those sizes.
+    pub fn generate<R: RngCore + CryptoRng + ?Sized>(bits: u64, rng: &mut R) -> Result<Self> {
+        if bits < MIN_GENERATED_BITS {
+            return Err(Error::InvalidParams("modulus is too small"));
+        }
+
+        let p = primes::generate_safe_prime(bits, rng);
+        let q: BigInt = (&p - 1u32) >> 1;
+
+        // Squares are exactly the order-q subgroup, so any square other
+        // than 1 generates it
+        let g = loop {
+            let h = rng.gen_bigint_range(&BigInt::from(2), &(&p - 1u32));
+            let g = h.modpow(&BigInt::from(2), &p);
+            if !g.is_one() {
+                break g;
+            }
+        };
+
+        Ok(Self::from_parts(p, q, g))
     }
 }
 
-impl VSSCommitments {
-    pub fn new(coefficients: &[i64], params: &VSSParams) -> Self {
-        let mut commitments = Vec::new();
+// The order-q subgroup of Z_p^*
+impl Group for VSSParams {
+    type Point = BigInt;
+
+    fn id(&self) -> String {
+        format!("modp:{:x}", self.p)
+    }
+
+    fn order(&self) -> BigInt {
+        self.q.clone()
+    }
+
+    fn generator(&self) -> BigInt {
+        self.g.clone()
+    }
+
+    fn identity(&self) -> BigInt {
+        BigInt::one()
+    }
 
-        for &coeff in coefficients {
-            let commitment = params.g.modpow(&BigInt::from(coeff), &params.p);
-            commitments.push(commitment);
+    fn add(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        (a * b) % &self.p
+    }
+
+    fn mul(&self, point: &BigInt, scalar: &BigInt) -> BigInt {
+        point.modpow(&reduce(scalar, &self.q), &self.p)
+    }
+
+    fn point_to_bytes(&self, point: &BigInt) -> Vec<u8> {
+        let length = self.p.bits().div_ceil(8) as usize;
+        let (_, bytes) = point.to_bytes_be();
+        let mut padded = vec![0u8; length.saturating_sub(bytes.len())];
+        padded.extend_from_slice(&bytes);
+        padded
+    }
+
+    fn point_from_bytes(&self, bytes: &[u8]) -> Option<BigInt> {
+        let point = BigInt::from_bytes_be(Sign::Plus, bytes);
+        self.is_element(&point).then_some(point)
+    }
+
+    // Hash into Z_p, then raise to the cofactor (p - 1) / q to land in
+    // the order-q subgroup. The 16 extra bytes keep the bias mod p
+    // negligible.
+    fn hash_to_point(&self, message: &[u8]) -> BigInt {
+        let length = self.p.bits().div_ceil(8) as usize + 16;
+        let cofactor = (&self.p - 1u32) / &self.q;
+
+        for counter in 0u32.. {
+            let bytes = hash::expand(b"shamir-modp-hash-to-group", counter, message, length);
+            let point = BigInt::from_bytes_be(Sign::Plus, &bytes).modpow(&cofactor, &self.p);
+            if !point.is_zero() && !point.is_one() {
+                return point;
+            }
         }
+        unreachable!("Ran out of counters hashing to the group")
+    }
+
+    // In the order-q subgroup of Z_p^*, unlike e.g. p - g
+    fn is_element(&self, point: &BigInt) -> bool {
+        *point > BigInt::zero() && *point < self.p && point.modpow(&self.q, &self.p).is_one()
+    }
+
+    // Built on first use, a few modpows' worth of work that pays for
+    // itself after a handful of exponentiations
+    fn mul_generator(&self, scalar: &BigInt) -> BigInt {
+        self.generator_table
+            .get_or_init(|| Arc::new(FixedBase::new(self, &self.g, multiexp::GENERATOR_WINDOW)))
+            .mul(self, scalar)
+    }
+
+    fn validate(&self) -> Result<()> {
+        VSSParams::validate(self)
+    }
+}
+
+impl<G: Group> VSSCommitments<G> {
+    // Coefficients are elements of Z_order, the exponent group of the
+    // generator
+    pub fn new(coefficients: &[BigInt], group: &G) -> Self {
+        let commitments = coefficients
+            .iter()
+            .map(|coeff| group.mul_generator(coeff))
+            .collect();
 
         Self { commitments }
     }
 
-    pub fn verify_share(&self, x: i64, share: i64, params: &VSSParams) -> bool {
-        let mut expected = BigInt::one();
-        let x_big = BigInt::from(x);
+    // Commitments received from the dealer, e.g. decoded with
+    // `Group::point_from_bytes`
+    pub fn from_points(commitments: Vec<G::Point>) -> Self {
+        Self { commitments }
+    }
+
+    pub fn points(&self) -> &[G::Point] {
+        &self.commitments
+    }
+
+    // Short hex tag of the commitment vector, safe to log
+    pub fn fingerprint(&self, group: &G) -> String {
+        fingerprint(group, &self.commitments)
+    }
+
+    // g^secret, the public key matching the shared secret
+    pub fn public_key(&self) -> Option<&G::Point> {
+        self.commitments.first()
+    }
+
+    // g^s_i for the participant at `x`, computed from public data only,
+    // so anyone can check what that participant later produces with s_i
+    // (signature shares, decryption shares, ...)
+    pub fn participant_key(&self, x: &BigInt, group: &G) -> G::Point {
+        evaluate_in_exponent(group, &self.commitments, &reduce(x, &group.order()))
+    }
+
+    pub fn participant_keys(&self, indices: &[BigInt], group: &G) -> Vec<G::Point> {
+        indices
+            .iter()
+            .map(|x| self.participant_key(x, group))
+            .collect()
+    }
+
+    // Checks g^share = prod C_i^(x^i), with x, the share and the
+    // exponents x^i all living in Z_order. `x` can be any non-zero
+    // participant index, not just 1..=n.
+    // Refuses groups that fail `Group::validate`.
+    pub fn verify_share(&self, x: &BigInt, share: &BigInt, group: &G) -> Result<bool> {
+        group.validate()?;
+        let order = group.order();
+        let x = reduce(x, &order);
+        if x.is_zero() {
+            return Ok(false);
+        }
 
-        for (i, commitment) in self.commitments.iter().enumerate() {
-            let power = x_big.modpow(&BigInt::from(i), &params.p);
-            let term = commitment.modpow(&power, &params.p);
-            expected = (expected * term) % &params.p;
+        Ok(self.participant_key(&x, group) == group.mul_generator(share))
+    }
+
+    // Verifies a whole share set and returns the positions in `shares`
+    // of those that fail, empty when all are valid. The shares must all
+    // come from one sharing over `field`, Z_order, with one commitment
+    // per coefficient up to their threshold. See `verify_points`.
+    pub fn verify_batch<F: Field>(
+        &self,
+        field: &F,
+        shares: &[Share<F>],
+        group: &G,
+    ) -> Result<Vec<usize>> {
+        if field.order() != group.order() {
+            return Err(Error::InvalidParams(
+                "sharing field must be the scalar field of the VSS group",
+            ));
+        }
+        let Some(first) = shares.first() else {
+            return Ok(vec![]);
+        };
+        if shares.iter().any(|share| {
+            share.field_id != field.id()
+                || !share.is_compatible(first)
+                || share.threshold != self.commitments.len()
+        }) {
+            return Err(Error::MismatchedShares);
         }
 
-        let actual = params.g.modpow(&BigInt::from(share), &params.p);
-        expected == actual
+        let indices: Vec<BigInt> = shares
+            .iter()
+            .map(|share| field.to_bigint(&share.index))
+            .collect();
+        let mut values: Vec<BigInt> = shares
+            .iter()
+            .map(|share| field.to_bigint(share.expose_secret()))
+            .collect();
+        let invalid = self.verify_points(&indices, &values, group);
+        values.wipe();
+        invalid
+    }
+
+    // Small-exponent batching: with random weights r_j, all shares are
+    // valid (up to 2^-128) iff
+    //   g^(sum r_j s_j) = prod C_i^(sum r_j x_j^i)
+    // which costs one fixed-base and one t-term multi-exponentiation no
+    // matter how many shares there are. Only when that fails are the
+    // shares checked one by one to name the bad ones.
+    //
+    // The batch is only sound in a prime-order group. A commitment such
+    // as p - g^a mod p, outside the order-q subgroup, adds a factor -1
+    // that cancels whenever the combined exponent is even, so about half
+    // of all batches would pass. Such commitments go straight to the
+    // per-share checks.
+    pub fn verify_points(
+        &self,
+        indices: &[BigInt],
+        values: &[BigInt],
+        group: &G,
+    ) -> Result<Vec<usize>> {
+        group.validate()?;
+        if indices.len() != values.len() {
+            return Err(Error::IndexCountMismatch {
+                expected: values.len(),
+                got: indices.len(),
+            });
+        }
+        let in_group = self.commitments.iter().all(|point| group.is_element(point));
+        if in_group && self.batch_holds(indices, values, group) {
+            return Ok(vec![]);
+        }
+
+        let mut invalid = vec![];
+        for (position, (x, value)) in indices.iter().zip(values).enumerate() {
+            if !self.verify_share(x, value, group)? {
+                invalid.push(position);
+            }
+        }
+        Ok(invalid)
+    }
+
+    fn batch_holds(&self, indices: &[BigInt], values: &[BigInt], group: &G) -> bool {
+        let order = group.order();
+        let indices: Vec<BigInt> = indices.iter().map(|x| reduce(x, &order)).collect();
+        // x = 0 never verifies, leave it to the per-share checks
+        if indices.iter().any(Zero::is_zero) {
+            return false;
+        }
+
+        let mut combined = BigInt::zero();
+        let mut exponents = vec![BigInt::zero(); self.commitments.len()];
+        for (x, value) in indices.iter().zip(values) {
+            let weight: BigInt = OsRng.gen_biguint(BATCH_WEIGHT_BITS).into();
+            let mut term = (&weight * value) % &order;
+            let sum = (&combined + &term) % &order;
+            replace_wiped(&mut combined, sum);
+            term.wipe();
+
+            // r_j * x_j^i for every commitment i
+            let mut power = weight;
+            for exponent in exponents.iter_mut() {
+                *exponent = (&*exponent + &power) % &order;
+                power = (power * x) % &order;
+            }
+        }
+
+        let holds =
+            group.mul_generator(&combined) == group.multi_exp(&self.commitments, &exponents);
+        combined.wipe();
+        holds
+    }
+}
+
+// prod C_i^(x^i): the committed polynomial evaluated at `x`, in the
+// exponent, as one multi-exponentiation
+pub fn evaluate_in_exponent<G: Group>(group: &G, commitments: &[G::Point], x: &BigInt) -> G::Point {
+    let order = group.order();
+    let mut powers = Vec::with_capacity(commitments.len());
+    let mut power = BigInt::one();
+
+    for _ in commitments {
+        let next = (&power * x) % &order;
+        powers.push(power);
+        power = next;
+    }
+    group.multi_exp(commitments, &powers)
+}
+
+// Short hex tag of a list of points, safe to log
+pub fn fingerprint<G: Group>(group: &G, points: &[G::Point]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(group.id().as_bytes());
+    for point in points {
+        let bytes = group.point_to_bytes(point);
+        hasher.update((bytes.len() as u32).to_be_bytes());
+        hasher.update(&bytes);
+    }
+    hasher.finalize()[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// Canonical representative of `value` mod `modulus`
+pub fn reduce(value: &BigInt, modulus: &BigInt) -> BigInt {
+    ((value % modulus) + modulus) % modulus
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::PrimeField;
+    use crate::shamir::SharmirModel;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn generated_group_is_a_safe_prime_subgroup() {
+        let params = VSSParams::generate(MIN_GENERATED_BITS, &mut OsRng).unwrap();
+        assert_eq!(params.p.bits(), MIN_GENERATED_BITS);
+        assert_eq!(&params.q * 2u32 + 1u32, params.p);
+        assert!(primes::is_probable_prime(&params.q, &mut OsRng));
+        assert!(!params.g.is_one());
+        assert!(params.g.modpow(&params.q, &params.p).is_one());
+
+        assert_eq!(
+            VSSParams::generate(MIN_GENERATED_BITS - 1, &mut OsRng).unwrap_err(),
+            Error::InvalidParams("modulus is too small")
+        );
+    }
+
+    #[test]
+    fn named_groups_are_safe_prime_groups() {
+        let groups = [
+            (VSSParams::modp_1536(), 1536),
+            (VSSParams::modp_2048(), 2048),
+            (VSSParams::modp_3072(), 3072),
+            (VSSParams::modp_4096(), 4096),
+            (VSSParams::modp_6144(), 6144),
+            (VSSParams::modp_8192(), 8192),
+            (VSSParams::ffdhe2048(), 2048),
+            (VSSParams::ffdhe3072(), 3072),
+            (VSSParams::ffdhe4096(), 4096),
+            (VSSParams::ffdhe6144(), 6144),
+            (VSSParams::ffdhe8192(), 8192),
+        ];
+        for (params, bits) in &groups {
+            assert_eq!(params.p.bits(), *bits);
+            // 2 is a square mod p exactly when p = 7 mod 8
+            assert_eq!(&params.p % 8u32, BigInt::from(7));
+            assert_eq!(&params.q * 2u32 + 1u32, params.p);
+        }
+
+        // The primality test is slow in debug builds, so only the smallest
+        let params = VSSParams::modp_1536();
+        assert!(params.g.modpow(&params.q, &params.p).is_one());
+        assert!(primes::is_probable_prime(&params.q, &mut OsRng));
+    }
+
+    #[test]
+    fn named_groups_validate_without_primality_tests() {
+        // Would take seconds per group with the full check
+        let groups = [
+            VSSParams::modp_2048(),
+            VSSParams::modp_8192(),
+            VSSParams::ffdhe2048(),
+            VSSParams::ffdhe8192(),
+        ];
+        for group in &groups {
+            assert_eq!(group.validate(), Ok(()));
+        }
+
+        assert!(VSSParams::modp_1536().validate().is_err());
+        let weaker = SecurityPolicy {
+            min_p_bits: 1536,
+            min_q_bits: 224,
+        };
+        assert_eq!(
+            VSSParams::modp_1536().with_policy(weaker).validate(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn parameters_from_outside_are_checked() {
+        assert_eq!(VSSParams::new().validate(), Ok(()));
+
+        // 2043 = 2 * 1021 + 1 = 3^2 * 227 is not prime
+        let composite =
+            VSSParams::from_parts(BigInt::from(2043), BigInt::from(1021), BigInt::from(4))
+                .with_policy(SecurityPolicy::none());
+        assert!(composite.validate().is_err());
+
+        let wrong_order = VSSParams::from_parts(
+            BigInt::from(2039),
+            BigInt::from(1019),
+            BigInt::from(2039 - 1),
+        )
+        .with_policy(SecurityPolicy::none());
+        assert!(wrong_order.validate().is_err());
+
+        // Fine structure, too small for the default policy
+        let small = VSSParams::from_parts(BigInt::from(2039), BigInt::from(1019), BigInt::from(4));
+        assert_eq!(
+            small.validate(),
+            Err(Error::InvalidParams(
+                "group is below the configured security level"
+            ))
+        );
+    }
+
+    #[test]
+    fn feldman_mod_p_rejects_tampered_shares() {
+        // 5 + 7x + 11x^2 over Z_1019, with shares at x = 1..=6
+        let group = VSSParams::new();
+        let commitments = VSSCommitments::new(&[5, 7, 11].map(BigInt::from), &group);
+        for x in (1..=6).map(BigInt::from) {
+            let value = (5 + 7 * &x + 11 * &x * &x) % group.q();
+            assert_eq!(commitments.verify_share(&x, &value, &group), Ok(true));
+            // The same value lifted by q is the same exponent
+            let lifted = &value + group.q();
+            assert_eq!(commitments.verify_share(&x, &lifted, &group), Ok(true));
+            let tampered = (&value + 1u32) % group.q();
+            assert_eq!(commitments.verify_share(&x, &tampered, &group), Ok(false));
+        }
+        assert_eq!(
+            commitments.verify_share(&BigInt::zero(), &BigInt::from(5), &group),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn batch_falls_back_to_name_bad_shares() {
+        // 5 + 7x + 11x^2 over Z_1019 again, at x = 1..=8
+        let group = VSSParams::new();
+        let commitments = VSSCommitments::new(&[5, 7, 11].map(BigInt::from), &group);
+        let indices: Vec<BigInt> = (1..=8).map(BigInt::from).collect();
+        let mut values: Vec<BigInt> = indices
+            .iter()
+            .map(|x| (5 + 7 * x + 11 * x * x) % group.q())
+            .collect();
+        assert_eq!(
+            commitments.verify_points(&indices, &values, &group),
+            Ok(vec![])
+        );
+
+        values[6] = (&values[6] + 1u32) % group.q();
+        values[2] = BigInt::zero();
+        assert_eq!(
+            commitments.verify_points(&indices, &values, &group),
+            Ok(vec![2, 6])
+        );
+        assert!(matches!(
+            commitments.verify_points(&indices[1..], &values, &group),
+            Err(Error::IndexCountMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn batch_skips_commitments_outside_the_subgroup() {
+        let group = VSSParams::new();
+        let honest = VSSCommitments::new(&[5, 7, 11].map(BigInt::from), &group);
+        let indices: Vec<BigInt> = (1..=5).map(BigInt::from).collect();
+        let values: Vec<BigInt> = indices
+            .iter()
+            .map(|x| (5 + 7 * x + 11 * x * x) % group.q())
+            .collect();
+
+        // p - g^7 has order 2q. Batched, the stray -1 cancels whenever
+        // the combined exponent is even, so this would pass half the time.
+        let mut points = honest.points().to_vec();
+        points[1] = group.p() - &points[1];
+        assert!(!group.is_element(&points[1]));
+        assert!(group.is_element(&honest.points()[1]));
+        let forged = VSSCommitments::from_points(points);
+
+        // One by one, the -1 survives at exactly the odd indices
+        for _ in 0..32 {
+            assert_eq!(
+                forged.verify_points(&indices, &values, &group),
+                Ok(vec![0, 2, 4])
+            );
+        }
+    }
+
+    #[test]
+    fn verify_batch_checks_share_metadata() {
+        let group = VSSParams::new();
+        let mut model = SharmirModel::with_vss(BigInt::from(1000), 5, 3, group.clone()).unwrap();
+        model.generate_shares(&mut OsRng);
+        let shares = model.get_shares().clone();
+        let field = model.field().clone();
+        let commitments = model.vss_commitments().unwrap();
+        assert_eq!(
+            commitments.verify_batch(&field, &shares, &group),
+            Ok(vec![])
+        );
+
+        // Commitments of a threshold-2 sharing don't cover these shares
+        let shorter = VSSCommitments::from_points(commitments.points()[..2].to_vec());
+        assert_eq!(
+            shorter.verify_batch(&field, &shares, &group),
+            Err(Error::MismatchedShares)
+        );
+
+        let mut other = SharmirModel::with_vss(BigInt::from(1000), 5, 3, group.clone()).unwrap();
+        other.generate_shares(&mut OsRng);
+        let mixed = [shares[0].clone(), other.get_shares()[1].clone()];
+        assert_eq!(
+            commitments.verify_batch(&field, &mixed, &group),
+            Err(Error::MismatchedShares)
+        );
+
+        let wrong_field = PrimeField::new(BigInt::from(1021));
+        assert!(matches!(
+            commitments.verify_batch(&wrong_field, &[], &group),
+            Err(Error::InvalidParams(_))
+        ));
     }
 }